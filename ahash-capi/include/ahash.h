@@ -0,0 +1,244 @@
+/*
+ * C API for aHash, a fast keyed non-cryptographic hash function.
+ *
+ * Link against libahash_capi (static or shared). See ahash-capi/README.md.
+ *
+ * Hashes are not portable: they depend on the CPU features available and may change between versions of the
+ * library. They must not be persisted or sent to other machines.
+ *
+ * Unless stated otherwise every pointer argument must be non-NULL and valid. A data pointer may be NULL only when
+ * its length is zero.
+ */
+
+#ifndef AHASH_H
+#define AHASH_H
+
+/* Generated from src/lib.rs by cbindgen. Do not edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * An in progress hash. Must not be used from more than one thread at a time.
+ */
+typedef struct ahash_hasher_t ahash_hasher_t;
+
+/**
+ * A set of keys used to create hashers. Immutable, so it may be shared between threads.
+ */
+typedef struct ahash_state_t ahash_state_t;
+
+/**
+ * A 128 bit hash value.
+ */
+typedef struct ahash128_t {
+  /**
+   * The low 64 bits. These are equal to the 64 bit hash of the same input.
+   */
+  uint64_t low;
+  /**
+   * The high 64 bits.
+   */
+  uint64_t high;
+} ahash128_t;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a state with keys obtained from the operating system's random number generator. Returns NULL if the random
+ * number generator fails.
+ */
+struct ahash_state_t *ahash_state_new(void);
+
+/**
+ * Creates a state from four keys. The same keys always produce the same hashes with a given build of the library.
+ */
+struct ahash_state_t *ahash_state_with_seeds(uint64_t k0,
+                                             uint64_t k1,
+                                             uint64_t k2,
+                                             uint64_t k3);
+
+/**
+ * Creates a state from a single key. This is equivalent to `ahash_state_with_seeds(seed, seed, seed, seed)`.
+ */
+struct ahash_state_t *ahash_state_with_seed(uint64_t seed);
+
+/**
+ * Creates a copy of a state.
+ *
+ * # Safety
+ * `state` must be a valid state handle.
+ */
+struct ahash_state_t *ahash_state_clone(const struct ahash_state_t *state);
+
+/**
+ * Releases a state. Passing NULL does nothing.
+ *
+ * # Safety
+ * `state` must be NULL or a state handle which has not already been freed.
+ */
+void ahash_state_free(struct ahash_state_t *state);
+
+/**
+ * Hashes a buffer. This is equivalent to `ahash_hasher_new`, a single `ahash_write` and `ahash_finish`.
+ * (Several writes are not equivalent to a single write of the concatenated data.)
+ *
+ * # Safety
+ * `state` must be a valid state handle and `data` must point to `len` readable bytes (or `len` must be zero).
+ */
+uint64_t ahash_hash_bytes(const struct ahash_state_t *state,
+                          const void *data,
+                          size_t len);
+
+/**
+ * Hashes a buffer to a 128 bit value. The low half is equal to the result of `ahash_hash_bytes`.
+ *
+ * # Safety
+ * `state` must be a valid state handle and `data` must point to `len` readable bytes (or `len` must be zero).
+ */
+struct ahash128_t ahash_hash_bytes128(const struct ahash_state_t *state,
+                                      const void *data,
+                                      size_t len);
+
+/**
+ * Hashes an integer key. This is faster than hashing its bytes, but gives a different result.
+ *
+ * # Safety
+ * `state` must be a valid state handle.
+ */
+uint64_t ahash_hash_u64(const struct ahash_state_t *state, uint64_t value);
+
+/**
+ * Hashes `count` buffers, writing the hash of `data[i]` (of length `lens[i]`) to `out[i]`.
+ *
+ * # Safety
+ * `state` must be a valid state handle, `data`, `lens` and `out` must point to arrays of `count` elements, and
+ * each `data[i]` must point to `lens[i]` readable bytes.
+ */
+void ahash_hash_batch(const struct ahash_state_t *state,
+                      const void *const *data,
+                      const size_t *lens,
+                      size_t count,
+                      uint64_t *out);
+
+/**
+ * Hashes `count` integer keys, writing `ahash_hash_u64(state, values[i])` to `out[i]`.
+ *
+ * # Safety
+ * `state` must be a valid state handle, and `values` and `out` must point to arrays of `count` elements.
+ */
+void ahash_hash_u64_batch(const struct ahash_state_t *state,
+                          const uint64_t *values,
+                          size_t count,
+                          uint64_t *out);
+
+/**
+ * Creates a hasher keyed by `state`. The state may be freed while the hasher is still in use.
+ *
+ * # Safety
+ * `state` must be a valid state handle.
+ */
+struct ahash_hasher_t *ahash_hasher_new(const struct ahash_state_t *state);
+
+/**
+ * Creates a hasher keyed by `state` which can also produce 128 bit hashes with `ahash_finish128`. Writes cost
+ * about twice as much as with a hasher from `ahash_hasher_new`.
+ *
+ * # Safety
+ * `state` must be a valid state handle.
+ */
+struct ahash_hasher_t *ahash_hasher_new128(const struct ahash_state_t *state);
+
+/**
+ * Creates a copy of a hasher, including the data written to it so far.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+struct ahash_hasher_t *ahash_hasher_clone(const struct ahash_hasher_t *hasher);
+
+/**
+ * Releases a hasher. Passing NULL does nothing.
+ *
+ * # Safety
+ * `hasher` must be NULL or a hasher handle which has not already been freed.
+ */
+void ahash_hasher_free(struct ahash_hasher_t *hasher);
+
+/**
+ * Writes data to a hasher.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle and `data` must point to `len` readable bytes (or `len` must be zero).
+ */
+void ahash_write(struct ahash_hasher_t *hasher,
+                 const void *data,
+                 size_t len);
+
+/**
+ * Writes an integer to a hasher.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+void ahash_write_u8(struct ahash_hasher_t *hasher, uint8_t value);
+
+/**
+ * Writes an integer to a hasher.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+void ahash_write_u16(struct ahash_hasher_t *hasher, uint16_t value);
+
+/**
+ * Writes an integer to a hasher.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+void ahash_write_u32(struct ahash_hasher_t *hasher, uint32_t value);
+
+/**
+ * Writes an integer to a hasher.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+void ahash_write_u64(struct ahash_hasher_t *hasher, uint64_t value);
+
+/**
+ * Writes a 128 bit integer, given as two halves, to a hasher.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+void ahash_write_u128(struct ahash_hasher_t *hasher, uint64_t low, uint64_t high);
+
+/**
+ * Returns the hash of the data written so far. The hasher is not modified and more data may be written to it.
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle.
+ */
+uint64_t ahash_finish(const struct ahash_hasher_t *hasher);
+
+/**
+ * Writes the 128 bit hash of the data written so far to `out` and returns true. The low half is equal to the result
+ * of `ahash_finish`. Returns false, leaving `out` unchanged, if the hasher was not created by `ahash_hasher_new128`
+ * (or cloned from one that was).
+ *
+ * # Safety
+ * `hasher` must be a valid hasher handle and `out` must be valid for writes.
+ */
+bool ahash_finish128(const struct ahash_hasher_t *hasher,
+                     struct ahash128_t *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AHASH_H */
@@ -0,0 +1,27 @@
+#!/bin/sh
+# Builds the C API in release mode and installs the static and shared libraries, the header, a pkg-config file and a
+# CMake package configuration.
+# Usage: ./install.sh [prefix]   (defaults to /usr/local)
+set -e
+PREFIX="${1:-/usr/local}"
+cd "$(dirname "$0")"
+cargo build --release
+TARGET_DIR="$(cargo metadata --format-version 1 --no-deps | sed -n 's/.*"target_directory":"\([^"]*\)".*/\1/p')"
+VERSION="$(sed -n 's/^version = "\(.*\)"/\1/p' Cargo.toml)"
+VERSION_MAJOR="${VERSION%%.*}"
+VERSION_MINOR="$(echo "$VERSION" | cut -d. -f2)"
+case "$(uname)" in
+    Darwin) SHARED=libahash_capi.dylib; PRIVATE_LIBS="-lSystem -lc -lm" ;;
+    *) SHARED=libahash_capi.so; PRIVATE_LIBS="-lpthread -ldl -lm -lc" ;;
+esac
+# CMake lists are separated by semicolons.
+CMAKE_PRIVATE_LIBS="$(echo "$PRIVATE_LIBS" | tr ' ' ';')"
+mkdir -p "$PREFIX/lib/pkgconfig" "$PREFIX/lib/cmake/ahash" "$PREFIX/include"
+cp "$TARGET_DIR/release/libahash_capi.a" "$TARGET_DIR/release/$SHARED" "$PREFIX/lib/"
+cp include/ahash.h "$PREFIX/include/"
+sed -e "s|@PREFIX@|$PREFIX|" -e "s|@VERSION@|$VERSION|" -e "s|@PRIVATE_LIBS@|$PRIVATE_LIBS|" ahash.pc.in \
+    > "$PREFIX/lib/pkgconfig/ahash.pc"
+sed -e "s|@SHARED@|$SHARED|" -e "s|@CMAKE_PRIVATE_LIBS@|$CMAKE_PRIVATE_LIBS|" ahashConfig.cmake.in \
+    > "$PREFIX/lib/cmake/ahash/ahashConfig.cmake"
+sed -e "s|@VERSION@|$VERSION|" -e "s|@VERSION_MAJOR@|$VERSION_MAJOR|" -e "s|@VERSION_MINOR@|$VERSION_MINOR|" \
+    ahashConfigVersion.cmake.in > "$PREFIX/lib/cmake/ahash/ahashConfigVersion.cmake"
@@ -0,0 +1,347 @@
+//! C API for aHash.
+//!
+//! This crate builds `libahash_capi` as both a static and a shared library. The matching header,
+//! `include/ahash.h`, is generated from this file by cbindgen (see `cbindgen.toml` and `tests/header.rs`), so the doc
+//! comments on the exported items below are also the C documentation.
+//!
+//! Two opaque handle types are exposed:
+//! * `ahash_state_t` wraps a [RandomState]: a set of keys from which hashers are created. It is immutable, so it can
+//!   be shared between threads. It also holds a second [RandomState], derived from the first, which keys the high
+//!   half of 128 bit hashes.
+//! * `ahash_hasher_t` wraps an [AHasher] which data is written to incrementally, plus a second one for hashers
+//!   created with [ahash_hasher_new128].
+//!
+//! Handles are allocated by the library and must be released with the matching `_free` function.
+//!
+//! Hashing a buffer with [ahash_hash_bytes] gives the same result as creating a hasher, making a single call to
+//! [ahash_write] and calling [ahash_finish]. Note that, as with Rust's `Hasher`, several writes are not
+//! equivalent to a single write of the concatenated data.
+#![allow(non_camel_case_types)]
+
+use ahash::{AHasher, RandomState};
+use core::ffi::c_void;
+use core::hash::{BuildHasher, Hasher};
+use core::ptr;
+use core::slice;
+
+/// A set of keys used to create hashers. Immutable, so it may be shared between threads.
+pub struct ahash_state_t {
+    low: RandomState,
+    high: RandomState,
+}
+
+/// An in progress hash. Must not be used from more than one thread at a time.
+pub struct ahash_hasher_t {
+    low: AHasher,
+    high: Option<AHasher>,
+}
+
+/// A 128 bit hash value.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ahash128_t {
+    /// The low 64 bits. These are equal to the 64 bit hash of the same input.
+    pub low: u64,
+    /// The high 64 bits.
+    pub high: u64,
+}
+
+impl ahash_state_t {
+    /// The high half uses keys derived from `low`, so the two halves of a 128 bit hash are independently keyed.
+    fn new(low: RandomState) -> *mut ahash_state_t {
+        let high = low.derive("ahash-capi high half");
+        Box::into_raw(Box::new(ahash_state_t { low, high }))
+    }
+}
+
+impl ahash_hasher_t {
+    #[inline]
+    fn write_with(&mut self, write: impl Fn(&mut AHasher)) {
+        write(&mut self.low);
+        if let Some(high) = &mut self.high {
+            write(high);
+        }
+    }
+}
+
+#[inline]
+unsafe fn bytes<'a>(data: *const c_void, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data.cast(), len)
+    }
+}
+
+#[inline]
+fn hash_bytes(state: &RandomState, data: &[u8]) -> AHasher {
+    let mut hasher = state.build_hasher();
+    hasher.write(data);
+    hasher
+}
+
+/// Creates a state with keys obtained from the operating system's random number generator. Returns NULL if the random
+/// number generator fails.
+#[no_mangle]
+pub extern "C" fn ahash_state_new() -> *mut ahash_state_t {
+    match RandomState::try_new() {
+        Ok(state) => ahash_state_t::new(state),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Creates a state from four keys. The same keys always produce the same hashes with a given build of the library.
+#[no_mangle]
+pub extern "C" fn ahash_state_with_seeds(k0: u64, k1: u64, k2: u64, k3: u64) -> *mut ahash_state_t {
+    ahash_state_t::new(RandomState::with_seeds(k0, k1, k2, k3))
+}
+
+/// Creates a state from a single key. This is equivalent to `ahash_state_with_seeds(seed, seed, seed, seed)`.
+#[no_mangle]
+pub extern "C" fn ahash_state_with_seed(seed: u64) -> *mut ahash_state_t {
+    ahash_state_with_seeds(seed, seed, seed, seed)
+}
+
+/// Creates a copy of a state.
+///
+/// # Safety
+/// `state` must be a valid state handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_state_clone(state: *const ahash_state_t) -> *mut ahash_state_t {
+    let state = &*state;
+    Box::into_raw(Box::new(ahash_state_t {
+        low: state.low.clone(),
+        high: state.high.clone(),
+    }))
+}
+
+/// Releases a state. Passing NULL does nothing.
+///
+/// # Safety
+/// `state` must be NULL or a state handle which has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_state_free(state: *mut ahash_state_t) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
+
+/// Hashes a buffer. This is equivalent to `ahash_hasher_new`, a single `ahash_write` and `ahash_finish`.
+/// (Several writes are not equivalent to a single write of the concatenated data.)
+///
+/// # Safety
+/// `state` must be a valid state handle and `data` must point to `len` readable bytes (or `len` must be zero).
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hash_bytes(state: *const ahash_state_t, data: *const c_void, len: usize) -> u64 {
+    hash_bytes(&(*state).low, bytes(data, len)).finish()
+}
+
+/// Hashes a buffer to a 128 bit value. The low half is equal to the result of `ahash_hash_bytes`.
+///
+/// # Safety
+/// `state` must be a valid state handle and `data` must point to `len` readable bytes (or `len` must be zero).
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hash_bytes128(
+    state: *const ahash_state_t,
+    data: *const c_void,
+    len: usize,
+) -> ahash128_t {
+    let state = &*state;
+    let data = bytes(data, len);
+    ahash128_t {
+        low: hash_bytes(&state.low, data).finish(),
+        high: hash_bytes(&state.high, data).finish(),
+    }
+}
+
+/// Hashes an integer key. This is faster than hashing its bytes, but gives a different result.
+///
+/// # Safety
+/// `state` must be a valid state handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hash_u64(state: *const ahash_state_t, value: u64) -> u64 {
+    (*state).low.hash_key(&value)
+}
+
+/// Hashes `count` buffers, writing the hash of `data[i]` (of length `lens[i]`) to `out[i]`.
+///
+/// # Safety
+/// `state` must be a valid state handle, `data`, `lens` and `out` must point to arrays of `count` elements, and
+/// each `data[i]` must point to `lens[i]` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hash_batch(
+    state: *const ahash_state_t,
+    data: *const *const c_void,
+    lens: *const usize,
+    count: usize,
+    out: *mut u64,
+) {
+    if count == 0 {
+        return;
+    }
+    let state = &(*state).low;
+    let data = slice::from_raw_parts(data, count);
+    let lens = slice::from_raw_parts(lens, count);
+    let out = slice::from_raw_parts_mut(out, count);
+    for ((out, &data), &len) in out.iter_mut().zip(data).zip(lens) {
+        *out = hash_bytes(state, bytes(data, len)).finish();
+    }
+}
+
+/// Hashes `count` integer keys, writing `ahash_hash_u64(state, values[i])` to `out[i]`.
+///
+/// # Safety
+/// `state` must be a valid state handle, and `values` and `out` must point to arrays of `count` elements.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hash_u64_batch(
+    state: *const ahash_state_t,
+    values: *const u64,
+    count: usize,
+    out: *mut u64,
+) {
+    if count == 0 {
+        return;
+    }
+    let state = &(*state).low;
+    let values = slice::from_raw_parts(values, count);
+    let out = slice::from_raw_parts_mut(out, count);
+    for (out, value) in out.iter_mut().zip(values) {
+        *out = state.hash_key(value);
+    }
+}
+
+/// Creates a hasher keyed by `state`. The state may be freed while the hasher is still in use.
+///
+/// # Safety
+/// `state` must be a valid state handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hasher_new(state: *const ahash_state_t) -> *mut ahash_hasher_t {
+    Box::into_raw(Box::new(ahash_hasher_t {
+        low: (*state).low.build_hasher(),
+        high: None,
+    }))
+}
+
+/// Creates a hasher keyed by `state` which can also produce 128 bit hashes with `ahash_finish128`. Writes cost
+/// about twice as much as with a hasher from `ahash_hasher_new`.
+///
+/// # Safety
+/// `state` must be a valid state handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hasher_new128(state: *const ahash_state_t) -> *mut ahash_hasher_t {
+    let state = &*state;
+    Box::into_raw(Box::new(ahash_hasher_t {
+        low: state.low.build_hasher(),
+        high: Some(state.high.build_hasher()),
+    }))
+}
+
+/// Creates a copy of a hasher, including the data written to it so far.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hasher_clone(hasher: *const ahash_hasher_t) -> *mut ahash_hasher_t {
+    let hasher = &*hasher;
+    Box::into_raw(Box::new(ahash_hasher_t {
+        low: hasher.low.clone(),
+        high: hasher.high.clone(),
+    }))
+}
+
+/// Releases a hasher. Passing NULL does nothing.
+///
+/// # Safety
+/// `hasher` must be NULL or a hasher handle which has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_hasher_free(hasher: *mut ahash_hasher_t) {
+    if !hasher.is_null() {
+        drop(Box::from_raw(hasher));
+    }
+}
+
+/// Writes data to a hasher.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle and `data` must point to `len` readable bytes (or `len` must be zero).
+#[no_mangle]
+pub unsafe extern "C" fn ahash_write(hasher: *mut ahash_hasher_t, data: *const c_void, len: usize) {
+    let data = bytes(data, len);
+    (*hasher).write_with(|h| h.write(data));
+}
+
+/// Writes an integer to a hasher.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_write_u8(hasher: *mut ahash_hasher_t, value: u8) {
+    (*hasher).write_with(|h| h.write_u8(value));
+}
+
+/// Writes an integer to a hasher.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_write_u16(hasher: *mut ahash_hasher_t, value: u16) {
+    (*hasher).write_with(|h| h.write_u16(value));
+}
+
+/// Writes an integer to a hasher.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_write_u32(hasher: *mut ahash_hasher_t, value: u32) {
+    (*hasher).write_with(|h| h.write_u32(value));
+}
+
+/// Writes an integer to a hasher.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_write_u64(hasher: *mut ahash_hasher_t, value: u64) {
+    (*hasher).write_with(|h| h.write_u64(value));
+}
+
+/// Writes a 128 bit integer, given as two halves, to a hasher.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_write_u128(hasher: *mut ahash_hasher_t, low: u64, high: u64) {
+    let value = ((high as u128) << 64) | low as u128;
+    (*hasher).write_with(|h| h.write_u128(value));
+}
+
+/// Returns the hash of the data written so far. The hasher is not modified and more data may be written to it.
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_finish(hasher: *const ahash_hasher_t) -> u64 {
+    (*hasher).low.finish()
+}
+
+/// Writes the 128 bit hash of the data written so far to `out` and returns true. The low half is equal to the result
+/// of `ahash_finish`. Returns false, leaving `out` unchanged, if the hasher was not created by `ahash_hasher_new128`
+/// (or cloned from one that was).
+///
+/// # Safety
+/// `hasher` must be a valid hasher handle and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ahash_finish128(hasher: *const ahash_hasher_t, out: *mut ahash128_t) -> bool {
+    let hasher = &*hasher;
+    match &hasher.high {
+        Some(high) => {
+            *out = ahash128_t {
+                low: hasher.low.finish(),
+                high: high.finish(),
+            };
+            true
+        }
+        None => false,
+    }
+}
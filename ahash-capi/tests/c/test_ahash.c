@@ -0,0 +1,155 @@
+/* Tests for the C API. Built and run by tests/c_tests.rs. */
+#include "ahash.h"
+
+#include <stdio.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(cond)                                                          \
+    do {                                                                     \
+        if (!(cond)) {                                                       \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            failures++;                                                      \
+        }                                                                    \
+    } while (0)
+
+static void test_seeded_states_are_deterministic(void) {
+    ahash_state_t *a = ahash_state_with_seeds(1, 2, 3, 4);
+    ahash_state_t *b = ahash_state_with_seeds(1, 2, 3, 4);
+    ahash_state_t *c = ahash_state_with_seeds(1, 2, 3, 5);
+    ahash_state_t *d = ahash_state_clone(a);
+    const char *text = "hello world";
+    uint64_t expected = ahash_hash_bytes(a, text, strlen(text));
+    CHECK(ahash_hash_bytes(b, text, strlen(text)) == expected);
+    CHECK(ahash_hash_bytes(d, text, strlen(text)) == expected);
+    CHECK(ahash_hash_bytes(c, text, strlen(text)) != expected);
+    CHECK(ahash_hash_bytes(a, "hello", 5) != expected);
+    CHECK(ahash_hash_bytes(a, NULL, 0) == ahash_hash_bytes(a, "", 0));
+    ahash_state_free(a);
+    ahash_state_free(b);
+    ahash_state_free(c);
+    ahash_state_free(d);
+    ahash_state_free(NULL);
+}
+
+static void test_random_states_differ(void) {
+    ahash_state_t *a = ahash_state_new();
+    ahash_state_t *b = ahash_state_new();
+    CHECK(a != NULL && b != NULL);
+    CHECK(ahash_hash_bytes(a, "key", 3) != ahash_hash_bytes(b, "key", 3));
+    CHECK(ahash_hash_u64(a, 7) != ahash_hash_u64(b, 7));
+    ahash_state_free(a);
+    ahash_state_free(b);
+
+    a = ahash_state_with_seed(5);
+    b = ahash_state_with_seeds(5, 5, 5, 5);
+    CHECK(ahash_hash_bytes(a, "key", 3) == ahash_hash_bytes(b, "key", 3));
+    ahash_state_free(a);
+    ahash_state_free(b);
+
+    /* Seeds which differ only in their high 32 bits must give different states. */
+    a = ahash_state_with_seed(5);
+    b = ahash_state_with_seed(5 | ((uint64_t)1 << 32));
+    CHECK(ahash_hash_bytes(a, "key", 3) != ahash_hash_bytes(b, "key", 3));
+    ahash_state_free(a);
+    ahash_state_free(b);
+}
+
+static void test_streaming_matches_one_shot(void) {
+    ahash_state_t *state = ahash_state_with_seeds(1, 2, 3, 4);
+    const char *text = "The quick brown fox jumps over the lazy dog";
+    ahash_hasher_t *hasher = ahash_hasher_new128(state);
+    ahash_hasher_t *narrow = ahash_hasher_new(state);
+    ahash_state_free(state);
+    ahash_write(hasher, text, strlen(text));
+    ahash_write(narrow, text, strlen(text));
+
+    state = ahash_state_with_seeds(1, 2, 3, 4);
+    CHECK(ahash_finish(hasher) == ahash_hash_bytes(state, text, strlen(text)));
+    CHECK(ahash_finish(narrow) == ahash_finish(hasher));
+    CHECK(ahash_finish(hasher) == ahash_finish(hasher));
+
+    ahash128_t wide;
+    CHECK(ahash_finish128(hasher, &wide));
+    CHECK(wide.low == ahash_finish(hasher));
+    CHECK(wide.high != wide.low);
+    ahash128_t one_shot = ahash_hash_bytes128(state, text, strlen(text));
+    CHECK(one_shot.low == wide.low && one_shot.high == wide.high);
+
+    /* The high half is keyed independently: it is not the low half of a different input. */
+    ahash_state_t *other = ahash_state_with_seeds(1, 2, 3, 5);
+    ahash128_t other_wide = ahash_hash_bytes128(other, text, strlen(text));
+    CHECK(other_wide.high != wide.high && other_wide.low != wide.low);
+    CHECK(ahash_hash_bytes128(state, "", 0).high != ahash_hash_bytes(state, "", 0));
+    ahash_state_free(other);
+
+    /* A hasher from ahash_hasher_new has no high half. */
+    ahash128_t untouched = {1, 2};
+    CHECK(!ahash_finish128(narrow, &untouched));
+    CHECK(untouched.low == 1 && untouched.high == 2);
+    ahash_hasher_t *narrow_copy = ahash_hasher_clone(narrow);
+    CHECK(!ahash_finish128(narrow_copy, &untouched));
+    ahash_hasher_free(narrow_copy);
+    ahash_hasher_free(narrow);
+
+    ahash_hasher_t *copy = ahash_hasher_clone(hasher);
+    ahash_write_u8(hasher, 1);
+    ahash_write_u16(hasher, 2);
+    ahash_write_u32(hasher, 3);
+    ahash_write_u64(hasher, 4);
+    ahash_write_u128(hasher, 5, 6);
+    CHECK(ahash_finish(copy) == wide.low);
+    ahash128_t copy_wide;
+    CHECK(ahash_finish128(copy, &copy_wide) && copy_wide.high == wide.high);
+    CHECK(ahash_finish(hasher) != wide.low);
+    ahash128_t changed;
+    CHECK(ahash_finish128(hasher, &changed) && changed.high != wide.high);
+
+    ahash_write_u8(copy, 1);
+    ahash_write_u16(copy, 2);
+    ahash_write_u32(copy, 3);
+    ahash_write_u64(copy, 4);
+    ahash_write_u128(copy, 5, 7);
+    CHECK(ahash_finish(copy) != ahash_finish(hasher));
+
+    ahash_hasher_free(hasher);
+    ahash_hasher_free(copy);
+    ahash_hasher_free(NULL);
+    ahash_state_free(state);
+}
+
+static void test_batch(void) {
+    ahash_state_t *state = ahash_state_with_seeds(1, 2, 3, 4);
+    const void *data[3] = {"a", "bc", NULL};
+    size_t lens[3] = {1, 2, 0};
+    uint64_t out[3];
+    ahash_hash_batch(state, data, lens, 3, out);
+    for (int i = 0; i < 3; i++) {
+        CHECK(out[i] == ahash_hash_bytes(state, data[i], lens[i]));
+    }
+    CHECK(out[0] != out[1] && out[1] != out[2]);
+
+    uint64_t values[4] = {0, 1, 2, UINT64_MAX};
+    uint64_t hashes[4];
+    ahash_hash_u64_batch(state, values, 4, hashes);
+    for (int i = 0; i < 4; i++) {
+        CHECK(hashes[i] == ahash_hash_u64(state, values[i]));
+    }
+    CHECK(hashes[0] != hashes[1]);
+    ahash_hash_batch(state, NULL, NULL, 0, NULL);
+    ahash_state_free(state);
+}
+
+int main(void) {
+    test_seeded_states_are_deterministic();
+    test_random_states_differ();
+    test_streaming_matches_one_shot();
+    test_batch();
+    if (failures) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    printf("All C API checks passed\n");
+    return 0;
+}
@@ -0,0 +1,74 @@
+//! Compiles `tests/c/test_ahash.c` against the static and shared libraries and runs it.
+#![cfg(unix)]
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The directory containing the libraries built for this test run. (Cargo places them alongside the test binary.)
+fn lib_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().to_path_buf()
+}
+
+fn compiler() -> String {
+    env::var("CC").unwrap_or_else(|_| "cc".to_string())
+}
+
+/// System libraries the Rust standard library depends on.
+fn system_libs() -> &'static [&'static str] {
+    if cfg!(target_os = "macos") {
+        &["-lSystem", "-lc", "-lm"]
+    } else {
+        &["-lpthread", "-ldl", "-lm", "-lc"]
+    }
+}
+
+fn compile_and_run(name: &str, link_args: &[&str], env_vars: &[(&str, &Path)]) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let exe = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let status = Command::new(compiler())
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/test_ahash.c"))
+        .arg("-o")
+        .arg(&exe)
+        .args(link_args)
+        .args(system_libs())
+        .status()
+        .expect("Failed to run the C compiler");
+    assert!(status.success(), "Compiling {} failed", name);
+
+    let mut command = Command::new(&exe);
+    for (key, value) in env_vars {
+        command.env(key, value);
+    }
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{} failed:\n{}",
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_c_api_static() {
+    let lib = lib_dir().join("libahash_capi.a");
+    assert!(lib.exists(), "{} not found", lib.display());
+    compile_and_run("test_ahash_static", &[lib.to_str().unwrap()], &[]);
+}
+
+#[test]
+fn test_c_api_shared() {
+    let dir = lib_dir();
+    let search = format!("-L{}", dir.display());
+    compile_and_run(
+        "test_ahash_shared",
+        &[&search, "-lahash_capi"],
+        &[("LD_LIBRARY_PATH", &dir), ("DYLD_LIBRARY_PATH", &dir)],
+    );
+}
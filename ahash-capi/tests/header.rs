@@ -0,0 +1,32 @@
+//! Checks that `include/ahash.h` matches the header cbindgen generates from `src/lib.rs`.
+//!
+//! Run with `AHASH_UPDATE_HEADER=1` to regenerate the header after changing the API.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_header_is_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate the header")
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = crate_dir.join("include/ahash.h");
+    if env::var_os("AHASH_UPDATE_HEADER").is_some() {
+        fs::write(&path, &generated).unwrap();
+    }
+    let existing = fs::read_to_string(&path).unwrap();
+    assert!(
+        existing == generated,
+        "{} is out of date. Rerun with AHASH_UPDATE_HEADER=1 to regenerate it.",
+        path.display()
+    );
+}
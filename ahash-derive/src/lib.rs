@@ -0,0 +1,234 @@
+//! Derive macros for [ahash](https://docs.rs/ahash).
+//!
+//! These are re-exported from `ahash` when its `derive` feature is enabled, and should be used from there.
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Type};
+
+/// Derives `Hash` for a struct, packing adjacent fixed-size fields into as few hasher calls as possible.
+///
+/// `#[derive(Hash)]` makes one call to the hasher per field, and with `AHasher` each call costs a full round of
+/// mixing. This derive instead concatenates runs of adjacent primitive fields (integers, `bool` and `char`) into a
+/// single `u64` or `u128` and passes that to `write_u64` or `write_u128`. Other fields are hashed normally.
+///
+/// Fields can be annotated with:
+/// * `#[ahash(skip)]` to leave the field out of the hash. (It should then also be ignored by `PartialEq`.)
+/// * `#[ahash(with = path::to::function)]` to hash the field with a function of the form
+///   `fn<H: Hasher>(&FieldType, &mut H)` instead of its `Hash` impl.
+///
+/// The result is a valid `Hash` implementation for any hasher, but it is not the same as that from `#[derive(Hash)]`.
+#[proc_macro_derive(AHash, attributes(ahash))]
+pub fn derive_ahash(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match ahash(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Derives `ahash::AHashKey` for a struct with exactly one field, using the hasher of that field's type.
+///
+/// The struct's `Hash` implementation should hash only that field (as `#[derive(Hash)]` does). Otherwise the hash is
+/// still correct, but may be slower to compute.
+#[proc_macro_derive(AHashKey)]
+pub fn derive_ahash_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match ahash_key(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn ahash_key(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(input.span(), "AHashKey can only be derived for structs")),
+    };
+    let field = match fields {
+        Fields::Named(f) if f.named.len() == 1 => &f.named[0],
+        Fields::Unnamed(f) if f.unnamed.len() == 1 => &f.unnamed[0],
+        _ => {
+            return Err(Error::new(
+                fields.span(),
+                "AHashKey can only be derived for structs with exactly one field",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let field_ty = &field.ty;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote!(where));
+    where_clause
+        .predicates
+        .push(syn::parse_quote!(#field_ty: ::ahash::AHashKey));
+    where_clause
+        .predicates
+        .push(syn::parse_quote!(Self: ::core::hash::Hash));
+    Ok(quote! {
+        impl #impl_generics ::ahash::AHashKey for #name #ty_generics #where_clause {
+            type Hasher = <#field_ty as ::ahash::AHashKey>::Hasher;
+
+            #[inline]
+            fn build_key_hasher(state: &::ahash::RandomState) -> Self::Hasher {
+                <#field_ty as ::ahash::AHashKey>::build_key_hasher(state)
+            }
+        }
+    })
+}
+
+/// How a single field is fed to the hasher.
+enum FieldHash {
+    /// A primitive of the given width which is packed with its neighbours.
+    Packed(Packed),
+    /// Any other field, hashed with the given statement.
+    Single(proc_macro2::TokenStream),
+}
+
+struct Packed {
+    bits: u32,
+    /// The field, converted to an unsigned integer of `bits` width.
+    value: proc_macro2::TokenStream,
+}
+
+/// The width in bits of primitive types which can be packed, and the unsigned type of that width
+/// (or `None` if the type is already that unsigned type).
+fn primitive_width(ty: &Type) -> Option<(u32, Option<&'static str>)> {
+    let path = match ty {
+        Type::Path(p) if p.qself.is_none() => &p.path,
+        Type::Paren(p) => return primitive_width(&p.elem),
+        Type::Group(g) => return primitive_width(&g.elem),
+        _ => return None,
+    };
+    let ident = path.get_ident()?.to_string();
+    let (bits, unsigned) = match ident.as_str() {
+        "u8" | "i8" | "bool" => (8, "u8"),
+        "u16" | "i16" => (16, "u16"),
+        "u32" | "i32" | "char" => (32, "u32"),
+        "u64" | "i64" | "usize" | "isize" => (64, "u64"),
+        "u128" | "i128" => (128, "u128"),
+        _ => return None,
+    };
+    Some((bits, Some(unsigned).filter(|u| *u != ident)))
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<(bool, Option<syn::Path>)> {
+    let mut skip = false;
+    let mut with = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("ahash")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else if meta.path.is_ident("with") {
+                with = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip` or `with = ...`"))
+            }
+        })?;
+    }
+    if skip && with.is_some() {
+        return Err(Error::new(field.span(), "`skip` and `with` cannot be used together"));
+    }
+    Ok((skip, with))
+}
+
+/// Emits a single `write_u64` or `write_u128` for a group of packed fields totalling at most 128 bits.
+fn flush(group: &mut Vec<Packed>, out: &mut Vec<proc_macro2::TokenStream>) {
+    if group.is_empty() {
+        return;
+    }
+    let total: u32 = group.iter().map(|p| p.bits).sum();
+    let (word, write) = if total <= 64 {
+        (quote!(u64), quote!(write_u64))
+    } else {
+        (quote!(u128), quote!(write_u128))
+    };
+    let mut shift = 0;
+    let mut parts = Vec::new();
+    for Packed { bits, value } in group.drain(..) {
+        let widened = if bits == 64 && total <= 64 || bits == 128 {
+            value
+        } else {
+            quote!((#value) as #word)
+        };
+        parts.push(if shift == 0 {
+            widened
+        } else {
+            quote!((#widened) << #shift)
+        });
+        shift += bits;
+    }
+    out.push(quote!(::core::hash::Hasher::#write(state, #(#parts)|*);));
+}
+
+fn ahash(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(input.span(), "AHash can only be derived for structs")),
+    };
+    let mut hashes = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let (skip, with) = parse_field_attrs(field)?;
+        if skip {
+            continue;
+        }
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(#index)
+            }
+        };
+        hashes.push(match (with, primitive_width(&field.ty)) {
+            (Some(with), _) => FieldHash::Single(quote!(#with(&self.#member, state);)),
+            (None, Some((bits, unsigned))) => {
+                let value = match unsigned {
+                    Some(unsigned) => {
+                        let unsigned = syn::Ident::new(unsigned, proc_macro2::Span::call_site());
+                        quote!(self.#member as #unsigned)
+                    }
+                    None => quote!(self.#member),
+                };
+                FieldHash::Packed(Packed { bits, value })
+            }
+            (None, None) => FieldHash::Single(quote!(::core::hash::Hash::hash(&self.#member, state);)),
+        });
+    }
+
+    let mut statements = Vec::new();
+    let mut group: Vec<Packed> = Vec::new();
+    for hash in hashes {
+        match hash {
+            FieldHash::Packed(packed) => {
+                if group.iter().map(|p| p.bits).sum::<u32>() + packed.bits > 128 {
+                    flush(&mut group, &mut statements);
+                }
+                group.push(packed);
+            }
+            FieldHash::Single(statement) => {
+                flush(&mut group, &mut statements);
+                statements.push(statement);
+            }
+        }
+    }
+    flush(&mut group, &mut statements);
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::core::hash::Hash));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #name #ty_generics #where_clause {
+            #[inline]
+            fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) {
+                #(#statements)*
+            }
+        }
+    })
+}
@@ -0,0 +1,178 @@
+//! Python bindings for aHash.
+//!
+//! The hashes computed here are identical to those computed in Rust with `RandomState::with_seeds` and
+//! [`hash_one`](ahash::RandomState::hash_one), provided both sides are built for the same target with the same
+//! version of aHash and the same toolchain channel. The Python types map to Rust as follows:
+//!
+//! | Python                       | Rust                                               |
+//! |------------------------------|----------------------------------------------------|
+//! | `hash_bytes(b)`              | `hash_one(Vec<u8>)`                                |
+//! | `hash_str(s)`                | `hash_one(String)`                                 |
+//! | `hash_int(i)` with `i >= 0`  | `hash_one(i)` for any unsigned integer up to `u64` |
+//! | `hash_int(i)` with `i < 0`   | `hash_one(i as i64)`                               |
+//!
+//! On stable Rust, hashing a reference (such as `&str` or `&[u8]`) gives the same result as hashing the owned value.
+//! On nightly, aHash specializes the owned string and byte types but not references to them, so only the owned forms
+//! above match.
+//!
+//! Build the extension with `maturin build --release` in this directory.
+use ahash::{AHasher, RandomState};
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::PyOverflowError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// Converts a Python int to the 64 bit value that Rust hashes for it.
+fn int_value(value: &Bound<'_, PyAny>) -> PyResult<u64> {
+    if let Ok(v) = value.extract::<u64>() {
+        Ok(v)
+    } else if let Ok(v) = value.extract::<i64>() {
+        Ok(v as u64)
+    } else {
+        Err(PyOverflowError::new_err("int must fit in a u64 or an i64"))
+    }
+}
+
+/// An array of integers accepted by `RandomState.hash_int_batch`.
+#[derive(FromPyObject)]
+enum IntArray<'py> {
+    U64(PyReadonlyArray1<'py, u64>),
+    I64(PyReadonlyArray1<'py, i64>),
+}
+
+/// A set of four keys. Equivalent to `ahash::RandomState::with_seeds(k0, k1, k2, k3)`.
+#[pyclass(name = "RandomState", module = "ahash_py", frozen)]
+#[derive(Clone)]
+pub struct PyRandomState(RandomState);
+
+#[pymethods]
+impl PyRandomState {
+    #[new]
+    fn new(k0: u64, k1: u64, k2: u64, k3: u64) -> Self {
+        PyRandomState(RandomState::with_seeds(k0, k1, k2, k3))
+    }
+
+    /// Hashes bytes as Rust hashes a `Vec<u8>`.
+    fn hash_bytes(&self, data: &[u8]) -> u64 {
+        self.0.hash_one(data.to_vec())
+    }
+
+    /// Hashes a string as Rust hashes a `String`.
+    fn hash_str(&self, data: String) -> u64 {
+        self.0.hash_one(data)
+    }
+
+    /// Hashes an int as Rust hashes a `u64` (or an `i64` if negative).
+    fn hash_int(&self, value: &Bound<'_, PyAny>) -> PyResult<u64> {
+        Ok(self.0.hash_one(int_value(value)?))
+    }
+
+    /// Hashes each element of a one dimensional uint64 or int64 numpy array with `hash_int`.
+    fn hash_int_batch<'py>(&self, py: Python<'py>, values: IntArray<'py>) -> Bound<'py, PyArray1<u64>> {
+        let state = &self.0;
+        let hashes: Vec<u64> = match values {
+            IntArray::U64(values) => {
+                let values = values.as_array();
+                py.detach(|| values.iter().map(|v| state.hash_one(*v)).collect())
+            }
+            IntArray::I64(values) => {
+                let values = values.as_array();
+                py.detach(|| values.iter().map(|v| state.hash_one(*v)).collect())
+            }
+        };
+        PyArray1::from_vec(py, hashes)
+    }
+
+    /// Hashes each element of a sequence of bytes with `hash_bytes`, returning a uint64 numpy array.
+    fn hash_bytes_batch<'py>(&self, py: Python<'py>, values: Vec<Bound<'py, PyBytes>>) -> Bound<'py, PyArray1<u64>> {
+        let state = &self.0;
+        let values: Vec<Vec<u8>> = values.iter().map(|v| v.as_bytes().to_vec()).collect();
+        let hashes = py.detach(|| values.into_iter().map(|v| state.hash_one(v)).collect());
+        PyArray1::from_vec(py, hashes)
+    }
+
+    /// Hashes each element of a sequence of strings with `hash_str`, returning a uint64 numpy array.
+    fn hash_str_batch<'py>(&self, py: Python<'py>, values: Vec<String>) -> Bound<'py, PyArray1<u64>> {
+        let state = &self.0;
+        let hashes = py.detach(|| values.into_iter().map(|v| state.hash_one(v)).collect());
+        PyArray1::from_vec(py, hashes)
+    }
+
+    /// Creates a streaming hasher. Equivalent to `BuildHasher::build_hasher`.
+    fn hasher(&self) -> PyHasher {
+        PyHasher(self.0.build_hasher())
+    }
+
+    fn __repr__(&self) -> &'static str {
+        "RandomState(..)"
+    }
+}
+
+/// A streaming hasher. Each method makes the corresponding call on Rust's `Hasher`.
+///
+/// Note that the one-shot methods of `RandomState` apply Rust's `Hash` implementations, which add a length prefix
+/// for bytes and a terminator for strings. Use `write_bytes` and `write_str` to replicate these.
+#[pyclass(name = "Hasher", module = "ahash_py")]
+#[derive(Clone)]
+pub struct PyHasher(AHasher);
+
+#[pymethods]
+impl PyHasher {
+    /// `Hasher::write`: writes raw bytes.
+    fn write(&mut self, data: &[u8]) {
+        self.0.write(data);
+    }
+
+    /// Writes bytes as `<[u8] as Hash>::hash` does (with a length prefix).
+    fn write_bytes(&mut self, data: &[u8]) {
+        data.hash(&mut self.0);
+    }
+
+    /// Writes a string as `<str as Hash>::hash` does.
+    fn write_str(&mut self, data: &str) {
+        data.hash(&mut self.0);
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.0.write_u8(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.0.write_u16(value);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.0.write_u32(value);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0.write_u64(value);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.0.write_i64(value);
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.0.write_u128(value);
+    }
+
+    /// Returns the hash of the data written so far. More data may be written afterwards.
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    /// Returns a copy of this hasher.
+    fn copy(&self) -> PyHasher {
+        self.clone()
+    }
+}
+
+/// Python bindings for aHash.
+#[pymodule]
+pub fn ahash_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyRandomState>()?;
+    m.add_class::<PyHasher>()?;
+    Ok(())
+}
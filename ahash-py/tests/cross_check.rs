@@ -0,0 +1,158 @@
+//! Runs the bindings in an embedded interpreter and checks that they agree with `ahash` used directly from Rust.
+use ahash::RandomState;
+use ahash_py::ahash_py;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::Once;
+
+const SEEDS: [u64; 4] = [0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344, 0xa409_3822_299f_31d0, 42];
+
+const STRINGS: &[&str] = &[
+    "",
+    "a",
+    "shard-17",
+    "The quick brown fox jumps over the lazy dog",
+    "日本語",
+];
+
+const INTS: &[i128] = &[0, 1, 255, 1 << 40, u64::MAX as i128, -1, i64::MIN as i128];
+
+fn run<T>(code: &str, f: impl FnOnce(&Bound<'_, PyDict>) -> T) -> T {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| pyo3::append_to_inittab!(ahash_py));
+    Python::attach(|py| {
+        let globals = PyDict::new(py);
+        let code = CString::new(code).unwrap();
+        py.run(&code, Some(&globals), None).unwrap();
+        f(&globals)
+    })
+}
+
+fn get<'py, T: pyo3::conversion::FromPyObjectOwned<'py>>(globals: &Bound<'py, PyDict>, name: &str) -> T {
+    let item = globals.get_item(name).unwrap().unwrap();
+    item.extract().map_err(Into::<PyErr>::into).unwrap()
+}
+
+fn setup() -> String {
+    format!(
+        "import ahash_py\nstate = ahash_py.RandomState({}, {}, {}, {})\nstrings = {:?}\nints = {:?}\n",
+        SEEDS[0], SEEDS[1], SEEDS[2], SEEDS[3], STRINGS, INTS
+    )
+}
+
+fn rust_state() -> RandomState {
+    RandomState::with_seeds(SEEDS[0], SEEDS[1], SEEDS[2], SEEDS[3])
+}
+
+#[test]
+fn test_one_shot_vectors() {
+    let code = setup()
+        + "str_hashes = [state.hash_str(s) for s in strings]\n\
+           bytes_hashes = [state.hash_bytes(s.encode()) for s in strings]\n\
+           int_hashes = [state.hash_int(i) for i in ints]\n";
+    let (str_hashes, bytes_hashes, int_hashes) = run(&code, |globals| {
+        (
+            get::<Vec<u64>>(globals, "str_hashes"),
+            get::<Vec<u64>>(globals, "bytes_hashes"),
+            get::<Vec<u64>>(globals, "int_hashes"),
+        )
+    });
+    let state = rust_state();
+    for (i, s) in STRINGS.iter().enumerate() {
+        assert_eq!(str_hashes[i], state.hash_one(s.to_string()), "hash_str({:?})", s);
+        assert_eq!(
+            bytes_hashes[i],
+            state.hash_one(s.as_bytes().to_vec()),
+            "hash_bytes({:?})",
+            s
+        );
+    }
+    for (i, &value) in INTS.iter().enumerate() {
+        let expected = if value >= 0 {
+            state.hash_one(value as u64)
+        } else {
+            state.hash_one(value as i64)
+        };
+        assert_eq!(int_hashes[i], expected, "hash_int({})", value);
+        if let Ok(small) = u32::try_from(value) {
+            assert_eq!(int_hashes[i], state.hash_one(small));
+        }
+    }
+}
+
+#[test]
+fn test_int_out_of_range() {
+    let error = run(
+        &(setup()
+            + "try:\n    state.hash_int(1 << 64)\n    error = ''\nexcept OverflowError as e:\n    error = str(e)\n"),
+        |globals| get::<String>(globals, "error"),
+    );
+    assert!(!error.is_empty());
+}
+
+#[test]
+fn test_streaming_vectors() {
+    let code = setup()
+        + "h = state.hasher()\n\
+           h.write(b'raw')\n\
+           h.write_u8(1)\n\
+           h.write_u16(2)\n\
+           h.write_u32(3)\n\
+           h.write_u64(4)\n\
+           h.write_i64(-5)\n\
+           h.write_u128(6 << 100)\n\
+           copy = h.copy()\n\
+           h.write_str('text')\n\
+           h.write_bytes(b'bytes')\n\
+           streamed = h.finish()\n\
+           copied = copy.finish()\n";
+    let (streamed, copied) = run(&code, |globals| {
+        (get::<u64>(globals, "streamed"), get::<u64>(globals, "copied"))
+    });
+    let mut hasher = rust_state().build_hasher();
+    hasher.write(b"raw");
+    hasher.write_u8(1);
+    hasher.write_u16(2);
+    hasher.write_u32(3);
+    hasher.write_u64(4);
+    hasher.write_i64(-5);
+    hasher.write_u128(6 << 100);
+    assert_eq!(copied, hasher.finish());
+    "text".hash(&mut hasher);
+    b"bytes"[..].hash(&mut hasher);
+    assert_eq!(streamed, hasher.finish());
+}
+
+/// The batch functions take and return numpy arrays, so this requires numpy. It fails rather than passing without
+/// checking anything if numpy is missing.
+#[test]
+fn test_batch_vectors() {
+    let has_numpy = run(
+        "try:\n    import numpy\n    has_numpy = True\nexcept ImportError:\n    has_numpy = False\n",
+        |globals| get::<bool>(globals, "has_numpy"),
+    );
+    assert!(
+        has_numpy,
+        "numpy is not installed, so the batch functions cannot be checked (install it with `pip install numpy`)"
+    );
+    let code = setup()
+        + "import numpy as np\n\
+           str_batch = state.hash_str_batch(strings).tolist()\n\
+           bytes_batch = state.hash_bytes_batch([s.encode() for s in strings]).tolist()\n\
+           u64_batch = state.hash_int_batch(np.array([i for i in ints if i >= 0], dtype=np.uint64)).tolist()\n\
+           i64_batch = state.hash_int_batch(np.array([i for i in ints if -2**63 <= i < 2**63], dtype=np.int64)).tolist()\n\
+           str_single = [state.hash_str(s) for s in strings]\n\
+           bytes_single = [state.hash_bytes(s.encode()) for s in strings]\n\
+           u64_single = [state.hash_int(i) for i in ints if i >= 0]\n\
+           i64_single = [state.hash_int(i) for i in ints if -2**63 <= i < 2**63]\n";
+    run(&code, |globals| {
+        for name in ["str", "bytes", "u64", "i64"] {
+            let batch: Vec<u64> = get(globals, &format!("{}_batch", name));
+            let single: Vec<u64> = get(globals, &format!("{}_single", name));
+            assert_eq!(batch, single, "{} batch", name);
+        }
+    });
+}
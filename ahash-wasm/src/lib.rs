@@ -0,0 +1,95 @@
+//! WebAssembly bindings for aHash.
+//!
+//! WebAssembly has no AES instructions, so this always uses aHash's fallback algorithm. It produces the same hashes as
+//! a native build of the same version of aHash which also uses the fallback (that is, a 64 bit target built without
+//! the `aes` target feature) for the same keys. The vectors in `tests/fallback_golden_vectors.txt` at the root of the
+//! repository are checked against both.
+//!
+//! Each method corresponds to hashing a value with its `Hash` impl and a hasher from
+//! `RandomState::with_seeds(k0, k1, k2, k3).build_hasher()`:
+//!
+//! | JavaScript                  | Rust                    |
+//! |-----------------------------|-------------------------|
+//! | `hashStr(string)`           | `&str`                  |
+//! | `hashBytes(Uint8Array)`     | `&[u8]`                 |
+//! | `hashU64(bigint)`           | `u64`                   |
+//! | `hashI64(bigint)`           | `i64`                   |
+//! | `hashU128(bigint)`          | `u128`                  |
+//!
+//! On stable Rust this is also what `hash_one` does. On nightly `hash_one` uses specialized hashers for strings and
+//! integers (see `BuildInfo::specialize`), so its results will not match.
+//!
+//! Hashes are returned as a `bigint`.
+//!
+//! ```js
+//! import { RandomState } from "ahash-wasm";
+//!
+//! const state = new RandomState(1n, 2n, 3n, 4n);
+//! const shard = state.hashStr("user-17") % 64n;
+//! ```
+use core::hash::{BuildHasher, Hash, Hasher};
+use wasm_bindgen::prelude::*;
+
+// aHash only uses the folded multiply on wasm with its `wasm-folded-multiply` feature, which Cargo.toml enables.
+// Without it every hash would differ from a native build, so refuse to build rather than produce them.
+const _: () = assert!(
+    ahash::FOLDED_MULTIPLY,
+    "ahash was built without cfg(folded_multiply), so its hashes would not match native builds"
+);
+
+/// A set of four keys used to hash values.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct RandomState {
+    inner: ahash::RandomState,
+}
+
+impl RandomState {
+    #[inline]
+    fn hash<T: Hash + ?Sized>(&self, value: &T) -> u64 {
+        let mut hasher = self.inner.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[wasm_bindgen]
+impl RandomState {
+    /// Creates a state from four keys. Equivalent to `RandomState::with_seeds(k0, k1, k2, k3)`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(k0: u64, k1: u64, k2: u64, k3: u64) -> RandomState {
+        RandomState {
+            inner: ahash::RandomState::with_seeds(k0, k1, k2, k3),
+        }
+    }
+
+    /// Hashes a string as Rust hashes a `&str`.
+    #[wasm_bindgen(js_name = hashStr)]
+    pub fn hash_str(&self, value: &str) -> u64 {
+        self.hash(value)
+    }
+
+    /// Hashes bytes as Rust hashes a `&[u8]`.
+    #[wasm_bindgen(js_name = hashBytes)]
+    pub fn hash_bytes(&self, value: &[u8]) -> u64 {
+        self.hash(value)
+    }
+
+    /// Hashes an integer as Rust hashes a `u64`. (Larger values are truncated to 64 bits.)
+    #[wasm_bindgen(js_name = hashU64)]
+    pub fn hash_u64(&self, value: u64) -> u64 {
+        self.hash(&value)
+    }
+
+    /// Hashes an integer as Rust hashes an `i64`. (Values outside its range are wrapped.)
+    #[wasm_bindgen(js_name = hashI64)]
+    pub fn hash_i64(&self, value: i64) -> u64 {
+        self.hash(&value)
+    }
+
+    /// Hashes an integer as Rust hashes a `u128`. (Larger values are truncated to 128 bits.)
+    #[wasm_bindgen(js_name = hashU128)]
+    pub fn hash_u128(&self, value: u128) -> u64 {
+        self.hash(&value)
+    }
+}
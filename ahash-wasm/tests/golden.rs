@@ -0,0 +1,51 @@
+//! Checks the bindings against the golden vectors shared with the native fallback tests.
+//!
+//! Run from `ahash-wasm/` with `wasm-pack test --node` (or `cargo test --target wasm32-unknown-unknown` with
+//! `wasm-bindgen-test-runner` installed). The same tests also run natively where the native backend is the fallback.
+#![cfg(any(target_arch = "wasm32", not(target_feature = "aes")))]
+
+use ahash_wasm::RandomState;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const VECTORS: &str = include_str!("../../tests/fallback_golden_vectors.txt");
+
+fn bytes(input: &str) -> Vec<u8> {
+    if input == "-" {
+        vec![]
+    } else {
+        hex::decode(input).unwrap()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_golden_vectors() {
+    let mut count = 0;
+    for line in VECTORS.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let seeds: Vec<u64> = fields[1..5].iter().map(|s| s.parse().unwrap()).collect();
+        let state = RandomState::new(seeds[0], seeds[1], seeds[2], seeds[3]);
+        let input = fields[5];
+        let hash = match fields[0] {
+            "str" => state.hash_str(&String::from_utf8(bytes(input)).unwrap()),
+            "bytes" => state.hash_bytes(&bytes(input)),
+            "u64" => state.hash_u64(input.parse().unwrap()),
+            "u128" => state.hash_u128(input.parse().unwrap()),
+            kind => panic!("Unknown kind {}", kind),
+        };
+        let expected = u64::from_str_radix(fields[6], 16).unwrap();
+        assert_eq!(hash, expected, "{}", line);
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_i64_matches_u64_bits() {
+    let state = RandomState::new(1, 2, 3, 4);
+    assert_eq!(state.hash_i64(-1), state.hash_u64(u64::MAX));
+    assert_eq!(state.hash_i64(i64::MIN), state.hash_u64(1 << 63));
+    assert_ne!(state.hash_i64(-1), state.hash_i64(1));
+}
@@ -0,0 +1,43 @@
+//! The hashers a trace can be replayed against.
+use ahash::trace::{replay, Stats, TraceError};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::hash::BuildHasherDefault;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HasherKind {
+    /// aHash with whichever backend it selected for this build.
+    Ahash,
+    /// aHash's fallback (non-AES) algorithm, even where AES is available.
+    AhashFallback,
+    /// SipHash 1-3, the standard library's default.
+    Sip,
+    /// FxHash, as used by rustc.
+    Fx,
+    /// 64 bit FNV-1a.
+    Fnv,
+}
+
+impl HasherKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            HasherKind::Ahash => "ahash",
+            HasherKind::AhashFallback => "ahash-fallback",
+            HasherKind::Sip => "sip",
+            HasherKind::Fx => "fx",
+            HasherKind::Fnv => "fnv",
+        }
+    }
+
+    /// Replays the trace once, using a newly keyed hasher where the hasher supports keys.
+    pub fn replay(self, trace: &[u8]) -> Result<Stats, TraceError> {
+        match self {
+            HasherKind::Ahash => replay(trace, ahash::RandomState::new()),
+            HasherKind::AhashFallback => replay(trace, ahash::FallbackRandomState::new()),
+            HasherKind::Sip => replay(trace, std::collections::hash_map::RandomState::new()),
+            HasherKind::Fx => replay(trace, BuildHasherDefault::<fxhash::FxHasher64>::default()),
+            HasherKind::Fnv => replay(trace, fnv::FnvBuildHasher::default()),
+        }
+    }
+}
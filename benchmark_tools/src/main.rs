@@ -1,34 +1,167 @@
-#![feature(buf_read_has_data_left)]
-
-mod persisting_hasher;
-mod data_reader;
-
-use persisting_hasher::*;
-use data_reader::*;
-use std::collections::HashMap;
-use std::fs::File;
-use std::time::SystemTime;
-use std::alloc::System;
-
-fn capture_output_example() {
-    let builder = PersistingHasherBuilder::default();
-    let mut map = HashMap::with_capacity_and_hasher(10, builder);
-    map.insert(1, 2);
-    map.insert(3, 4);
-    let builder = PersistingHasherBuilder::default();
-    let mut map = HashMap::with_capacity_and_hasher(10, builder);
-    map.insert("1", 2);
-    map.insert("3", 4);
-    PersistingHasherBuilder::default().flush();
+//! `ahash-bench`: compares hashers on recorded or synthesized workloads.
+//!
+//! Record a trace of a real program with `ahash::trace::TracingBuildHasher`, or generate one with
+//! `ahash-bench synthesize`, then time each hasher on it with `ahash-bench replay`.
+mod hashers;
+mod synthesize;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use hashers::HasherKind;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::process;
+use synthesize::{synthesize, Spec};
+
+#[derive(Parser)]
+#[command(
+    name = "ahash-bench",
+    about = "Compares hashers on recorded or synthesized workloads"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replays a trace against one or more hashers and reports how long each took.
+    Replay {
+        /// A trace written by `ahash::trace::TracingBuildHasher` or `synthesize`.
+        trace: PathBuf,
+        /// The hashers to compare. (Defaults to all of those available.)
+        #[arg(long = "hasher", value_enum, value_delimiter = ',')]
+        hashers: Vec<HasherKind>,
+        /// The number of timed replays per hasher (after one untimed warm up).
+        #[arg(long, default_value_t = 10)]
+        iterations: u32,
+        /// Print the results as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Writes a trace of hashing keys drawn from a mix of distributions.
+    Synthesize {
+        /// The key distributions and their weights, e.g. `integer:3,short-string:2,url:1,blob:1`.
+        #[arg(long, default_value = "integer")]
+        keys: Spec,
+        /// The number of keys to hash.
+        #[arg(long, default_value_t = 1_000_000)]
+        count: u64,
+        /// Seeds the key generator, so the same trace can be regenerated.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Where to write the trace.
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+/// The timings for one hasher.
+#[derive(Serialize)]
+struct Report {
+    hasher: HasherKind,
+    iterations: u32,
+    hashes: u64,
+    mean_ns: f64,
+    stddev_ns: f64,
+    min_ns: f64,
+    max_ns: f64,
+    hashes_per_second: f64,
+}
+
+impl Report {
+    fn new(hasher: HasherKind, hashes: u64, samples: &[f64]) -> Report {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / (n - 1.0).max(1.0);
+        Report {
+            hasher,
+            iterations: samples.len() as u32,
+            hashes,
+            mean_ns: mean,
+            stddev_ns: variance.sqrt(),
+            min_ns: samples.iter().cloned().fold(f64::INFINITY, f64::min),
+            max_ns: samples.iter().cloned().fold(0.0, f64::max),
+            hashes_per_second: hashes as f64 / (mean / 1e9),
+        }
+    }
+}
+
+fn run_replay(trace: PathBuf, mut hashers: Vec<HasherKind>, iterations: u32, json: bool) -> Result<(), String> {
+    let data = fs::read(&trace).map_err(|e| format!("{}: {}", trace.display(), e))?;
+    if hashers.is_empty() {
+        hashers = HasherKind::value_variants().to_vec();
+    }
+    if iterations == 0 {
+        return Err("--iterations must be at least 1".to_string());
+    }
+
+    let mut reports = Vec::new();
+    for hasher in hashers {
+        let warm_up = hasher
+            .replay(&data)
+            .map_err(|e| format!("{}: {}", trace.display(), e))?;
+        let samples: Vec<f64> = (0..iterations)
+            .map(|_| hasher.replay(&data).unwrap().elapsed.as_nanos() as f64)
+            .collect();
+        reports.push(Report::new(hasher, warm_up.calls.finish, &samples));
+        if reports.len() == 1 && !json {
+            println!(
+                "{}: {} hashes, {} calls, {} iterations",
+                trace.display(),
+                warm_up.calls.finish,
+                warm_up.calls.total(),
+                iterations
+            );
+            println!(
+                "{:<16} {:>12} {:>10} {:>12} {:>12} {:>14}",
+                "hasher", "mean", "stddev", "min", "max", "hashes/s"
+            );
+        }
+        if !json {
+            let r = reports.last().unwrap();
+            println!(
+                "{:<16} {:>10.3}ms {:>9.1}% {:>10.3}ms {:>10.3}ms {:>14.0}",
+                hasher.name(),
+                r.mean_ns / 1e6,
+                100.0 * r.stddev_ns / r.mean_ns,
+                r.min_ns / 1e6,
+                r.max_ns / 1e6,
+                r.hashes_per_second
+            );
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports).unwrap());
+    }
+    Ok(())
+}
+
+fn run_synthesize(keys: Spec, count: u64, seed: u64, output: PathBuf) -> Result<(), String> {
+    let error = |e: std::io::Error| format!("{}: {}", output.display(), e);
+    let file = File::create(&output).map_err(error)?;
+    synthesize(&keys, count, seed, BufWriter::new(file)).map_err(error)?;
+    Ok(())
 }
 
 fn main() {
-    // capture_output_example();
-
-    //Given a previously captured set of hashed data, time how long it takes using a different algorithm.
-    let file = File::open("hash_output-295253").unwrap();
-    let rand = ahash::RandomState::new();
-    let start_time = SystemTime::now();
-    let result = test_hasher(file, rand).unwrap();
-    println!("Completed after {:?} with result: {:x}", SystemTime::now().duration_since(start_time).unwrap(), result)
+    let result = match Cli::parse().command {
+        Command::Replay {
+            trace,
+            hashers,
+            iterations,
+            json,
+        } => run_replay(trace, hashers, iterations, json),
+        Command::Synthesize {
+            keys,
+            count,
+            seed,
+            output,
+        } => run_synthesize(keys, count, seed, output),
+    };
+    if let Err(message) = result {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,153 @@
+//! Generates traces of hashing keys drawn from a mix of distributions.
+use ahash::trace::TracingBuildHasher;
+use rand::distributions::{Alphanumeric, WeightedIndex};
+use rand::prelude::*;
+use std::hash::BuildHasher;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// A kind of key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// Uniformly random `u64`s.
+    Integer,
+    /// Alphanumeric `String`s of 4 to 16 characters.
+    ShortString,
+    /// URL-like `String`s, around 30 to 100 characters long.
+    Url,
+    /// Random `Vec<u8>`s of 256 bytes to 4 KiB.
+    Blob,
+}
+
+impl FromStr for KeyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "integer" => Ok(KeyKind::Integer),
+            "short-string" => Ok(KeyKind::ShortString),
+            "url" => Ok(KeyKind::Url),
+            "blob" => Ok(KeyKind::Blob),
+            _ => Err(format!(
+                "unknown key kind `{}` (expected integer, short-string, url or blob)",
+                s
+            )),
+        }
+    }
+}
+
+/// A weighted mix of key kinds, written as `kind[:weight],...`. For example `integer:3,url` draws three integers for
+/// every URL on average.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spec {
+    pub kinds: Vec<(KeyKind, u32)>,
+}
+
+impl FromStr for Spec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kinds = s
+            .split(',')
+            .map(|part| {
+                let (kind, weight) = match part.split_once(':') {
+                    Some((kind, weight)) => (
+                        kind,
+                        weight.parse().map_err(|_| format!("invalid weight `{}`", weight))?,
+                    ),
+                    None => (part, 1),
+                };
+                Ok((kind.trim().parse()?, weight))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if kinds.iter().all(|(_, weight)| *weight == 0) {
+            return Err("at least one key kind must have a non-zero weight".to_string());
+        }
+        Ok(Spec { kinds })
+    }
+}
+
+const HOSTS: &[&str] = &[
+    "example.com",
+    "api.example.com",
+    "cdn.example.net",
+    "static.example.org",
+];
+const SEGMENTS: &[&str] = &[
+    "users", "items", "v1", "v2", "search", "images", "orders", "products", "assets",
+];
+
+fn short_string(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(4..=16);
+    rng.sample_iter(Alphanumeric).take(len).map(char::from).collect()
+}
+
+fn url(rng: &mut StdRng) -> String {
+    let mut url = format!("https://{}", HOSTS.choose(rng).unwrap());
+    for _ in 0..rng.gen_range(1..=4) {
+        url.push('/');
+        url.push_str(SEGMENTS.choose(rng).unwrap());
+    }
+    url.push('/');
+    url.push_str(&short_string(rng));
+    if rng.gen_bool(0.5) {
+        url.push_str(&format!("?id={}", rng.gen_range(0..1_000_000)));
+    }
+    url
+}
+
+fn blob(rng: &mut StdRng) -> Vec<u8> {
+    let mut blob = vec![0; rng.gen_range(256..=4096)];
+    rng.fill_bytes(&mut blob);
+    blob
+}
+
+/// Writes a trace of hashing `count` keys drawn from `spec` to `out`. The same seed always produces the same trace.
+pub fn synthesize<W: Write>(spec: &Spec, count: u64, seed: u64, out: W) -> io::Result<W> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let choice = WeightedIndex::new(spec.kinds.iter().map(|(_, weight)| *weight)).unwrap();
+    let builder = TracingBuildHasher::new(ahash::RandomState::with_seed(seed as usize), out)?;
+    for _ in 0..count {
+        match spec.kinds[choice.sample(&mut rng)].0 {
+            KeyKind::Integer => builder.hash_one(rng.gen::<u64>()),
+            KeyKind::ShortString => builder.hash_one(short_string(&mut rng)),
+            KeyKind::Url => builder.hash_one(url(&mut rng)),
+            KeyKind::Blob => builder.hash_one(blob(&mut rng)),
+        };
+    }
+    Ok(builder.into_inner()?.unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hashers::HasherKind;
+    use clap::ValueEnum;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec: Spec = "integer:3, url,blob:0".parse().unwrap();
+        assert_eq!(
+            spec.kinds,
+            vec![(KeyKind::Integer, 3), (KeyKind::Url, 1), (KeyKind::Blob, 0)]
+        );
+        assert!("integer:x".parse::<Spec>().is_err());
+        assert!("float".parse::<Spec>().is_err());
+        assert!("url:0".parse::<Spec>().is_err());
+    }
+
+    #[test]
+    fn test_synthesized_traces_replay() {
+        let spec = "integer:4,short-string:3,url:2,blob:1".parse().unwrap();
+        let trace = synthesize(&spec, 1000, 7, Vec::new()).unwrap();
+        assert_eq!(trace, synthesize(&spec, 1000, 7, Vec::new()).unwrap());
+        assert_ne!(trace, synthesize(&spec, 1000, 8, Vec::new()).unwrap());
+        for hasher in HasherKind::value_variants() {
+            let stats = hasher.replay(&trace).unwrap();
+            assert_eq!(stats.calls.finish, 1000, "{}", hasher.name());
+            assert!(stats.calls.write_u64 > 0 && stats.calls.write > 0);
+        }
+        let fx = HasherKind::Fx.replay(&trace).unwrap().checksum;
+        assert_eq!(fx, HasherKind::Fx.replay(&trace).unwrap().checksum);
+    }
+}
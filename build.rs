@@ -5,6 +5,8 @@ use std::env;
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rustc-check-cfg=cfg(specialize)");
+    // Set by `cargo fuzz`, see `fuzz/`.
+    println!("cargo:rustc-check-cfg=cfg(fuzzing)");
     if let Some(true) = version_check::supports_feature("specialize") {
         println!("cargo:rustc-cfg=specialize");
     }
@@ -16,6 +18,8 @@ fn main() {
         || arch.eq_ignore_ascii_case("powerpc64")
         || arch.eq_ignore_ascii_case("riscv64gc")
         || arch.eq_ignore_ascii_case("s390x")
+        || ((arch.eq_ignore_ascii_case("wasm32") || arch.eq_ignore_ascii_case("wasm64"))
+            && env::var_os("CARGO_FEATURE_WASM_FOLDED_MULTIPLY").is_some())
     {
         println!("cargo:rustc-cfg=folded_multiply");
     }
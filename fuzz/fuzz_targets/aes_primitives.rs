@@ -0,0 +1,43 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u128, u128)| {
+    let (value, key) = input;
+    #[cfg(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes"),
+        all(target_arch = "aarch64", target_feature = "aes"),
+    ))]
+    {
+        use ahash::fuzzing::{aesdec, aesenc, soft_aesdec, soft_aesenc};
+        assert_eq!(
+            aesenc(value, key),
+            soft_aesenc(value, key),
+            "aesenc({:x}, {:x})",
+            value,
+            key
+        );
+        assert_eq!(
+            aesdec(value, key),
+            soft_aesdec(value, key),
+            "aesdec({:x}, {:x})",
+            value,
+            key
+        );
+        // The hasher chains rounds, so also check that results feed back in the same way.
+        let enc = aesenc(value, key);
+        assert_eq!(
+            aesdec(enc, enc),
+            soft_aesdec(enc, enc),
+            "aesdec of aesenc({:x}, {:x})",
+            value,
+            key
+        );
+    }
+    // Without hardware AES the hasher never uses these rounds, so there is nothing to compare.
+    #[cfg(not(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes"),
+        all(target_arch = "aarch64", target_feature = "aes"),
+    )))]
+    let _ = (value, key);
+});
@@ -0,0 +1,182 @@
+#![no_main]
+
+use ahash::fuzzing::SPECIALIZED;
+use ahash::RandomState;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Arbitrary, Clone, Debug, Hash)]
+struct Record {
+    id: u64,
+    name: String,
+    tags: Vec<(u8, i32)>,
+    parent: Option<Box<Record>>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Value {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Isize(isize),
+    Bool(bool),
+    Char(char),
+    Pair((u32, u32)),
+    Str(String),
+    Bytes(Vec<u8>),
+    Array([u8; 7]),
+    Words(Vec<u64>),
+    Tuple((u16, String, i64)),
+    Strings(Vec<Option<String>>),
+    Record(Record),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    seeds: [u64; 4],
+    values: Vec<Value>,
+}
+
+fn reference<T: Hash + ?Sized>(state: &RandomState, value: &T) -> u64 {
+    let mut hasher = state.build_hasher();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Types `hash_one` may specialize, both by value and by reference.
+fn check_scalar<T: Hash + Copy + Debug>(state: &RandomState, value: T) {
+    let hash = state.hash_one(value);
+    assert_eq!(hash, state.hash_one(&value), "{:?} by value and by reference", value);
+    assert_eq!(hash, BuildHasher::hash_one(state, value), "{:?} via BuildHasher", value);
+    if !SPECIALIZED {
+        assert_eq!(hash, reference(state, &value), "{:?} via build_hasher", value);
+    }
+}
+
+/// Types that are never specialized, which must always match `build_hasher`.
+fn check_generic<T: Hash + Clone + Debug>(state: &RandomState, value: &T) {
+    let hash = reference(state, value);
+    assert_eq!(hash, state.hash_one(value.clone()), "{:?} by value", value);
+    assert_eq!(hash, state.hash_one(value), "{:?} by reference", value);
+    assert_eq!(hash, BuildHasher::hash_one(state, value), "{:?} via BuildHasher", value);
+}
+
+/// Owned strings (and byte strings) may be specialized, but must agree with each other. References to them (which is
+/// how maps hash keys and look them up) are never specialized.
+fn check_str(state: &RandomState, value: &str) {
+    let owned = state.hash_one(value.to_string());
+    assert_eq!(
+        owned,
+        state.hash_one(Box::<str>::from(value)),
+        "{:?} as Box<str>",
+        value
+    );
+    assert_eq!(owned, state.hash_one(Rc::<str>::from(value)), "{:?} as Rc<str>", value);
+    assert_eq!(
+        owned,
+        state.hash_one(Arc::<str>::from(value)),
+        "{:?} as Arc<str>",
+        value
+    );
+    assert_eq!(owned, state.hash_one(Cow::Borrowed(value)), "{:?} as Cow<str>", value);
+    assert_eq!(
+        owned,
+        BuildHasher::hash_one(state, value.to_string()),
+        "{:?} via BuildHasher",
+        value
+    );
+    let borrowed = reference(state, value);
+    assert_eq!(borrowed, state.hash_one(value), "{:?} as &str", value);
+    assert_eq!(borrowed, state.hash_one(&value.to_string()), "{:?} as &String", value);
+    assert_eq!(
+        borrowed,
+        BuildHasher::hash_one(state, value),
+        "&{:?} via BuildHasher",
+        value
+    );
+    if !SPECIALIZED {
+        assert_eq!(owned, borrowed, "{:?} by value and by reference", value);
+    }
+}
+
+fn check_bytes(state: &RandomState, value: &[u8]) {
+    let owned = state.hash_one(value.to_vec());
+    assert_eq!(
+        owned,
+        state.hash_one(Box::<[u8]>::from(value)),
+        "{:?} as Box<[u8]>",
+        value
+    );
+    assert_eq!(
+        owned,
+        BuildHasher::hash_one(state, value.to_vec()),
+        "{:?} via BuildHasher",
+        value
+    );
+    let borrowed = reference(state, value);
+    assert_eq!(borrowed, state.hash_one(value), "{:?} as &[u8]", value);
+    assert_eq!(borrowed, state.hash_one(&value.to_vec()), "{:?} as &Vec<u8>", value);
+    if !SPECIALIZED {
+        assert_eq!(owned, borrowed, "{:?} by value and by reference", value);
+    }
+}
+
+fn check_array<const N: usize>(state: &RandomState, value: [u8; N]) {
+    check_bytes(state, &value);
+    assert_eq!(
+        state.hash_one(value),
+        state.hash_one(value.to_vec()),
+        "{:?} as an array",
+        value
+    );
+    assert_eq!(
+        state.hash_one(&value),
+        reference(state, &value[..]),
+        "&{:?} as an array",
+        value
+    );
+}
+
+fuzz_target!(|input: Input| {
+    let [k0, k1, k2, k3] = input.seeds;
+    let state = RandomState::with_seeds(k0, k1, k2, k3);
+    for value in &input.values {
+        match value {
+            Value::U8(v) => check_scalar(&state, *v),
+            Value::U16(v) => check_scalar(&state, *v),
+            Value::U32(v) => check_scalar(&state, *v),
+            Value::U64(v) => check_scalar(&state, *v),
+            Value::U128(v) => check_scalar(&state, *v),
+            Value::Usize(v) => check_scalar(&state, *v),
+            Value::I8(v) => check_scalar(&state, *v),
+            Value::I16(v) => check_scalar(&state, *v),
+            Value::I32(v) => check_scalar(&state, *v),
+            Value::I64(v) => check_scalar(&state, *v),
+            Value::I128(v) => check_scalar(&state, *v),
+            Value::Isize(v) => check_scalar(&state, *v),
+            Value::Bool(v) => check_scalar(&state, *v),
+            Value::Char(v) => check_scalar(&state, *v),
+            Value::Pair(v) => check_scalar(&state, *v),
+            Value::Str(v) => check_str(&state, v),
+            Value::Bytes(v) => check_bytes(&state, v),
+            Value::Array(v) => check_array(&state, *v),
+            Value::Words(v) => check_generic(&state, v),
+            Value::Tuple(v) => check_generic(&state, v),
+            Value::Strings(v) => check_generic(&state, v),
+            Value::Record(v) => check_generic(&state, v),
+        }
+    }
+});
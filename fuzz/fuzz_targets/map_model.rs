@@ -0,0 +1,89 @@
+#![no_main]
+
+use ahash::{AHashMap, RandomState};
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use std::collections::BTreeMap;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Insert(String, u32),
+    Remove(String),
+    Get(String),
+    GetMut(String, u32),
+    Entry(String, u32),
+    Retain(u32),
+    Extend(Vec<(String, u32)>),
+    Clear,
+    Reserve(u8),
+    ShrinkToFit,
+    Clone,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    seeds: [u64; 4],
+    ops: Vec<Op>,
+}
+
+fn assert_same(map: &AHashMap<String, u32>, model: &BTreeMap<String, u32>) {
+    assert_eq!(map.len(), model.len());
+    let mut entries: Vec<_> = map.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    entries.sort();
+    assert!(entries.iter().map(|(k, v)| (k, v)).eq(model.iter()));
+    for (key, value) in model {
+        assert_eq!(map.get(key.as_str()), Some(value), "lookup of {:?}", key);
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let [k0, k1, k2, k3] = input.seeds;
+    let mut map = AHashMap::with_hasher(RandomState::with_seeds(k0, k1, k2, k3));
+    let mut model = BTreeMap::new();
+    for op in input.ops {
+        match op {
+            Op::Insert(key, value) => assert_eq!(map.insert(key.clone(), value), model.insert(key, value)),
+            Op::Remove(key) => assert_eq!(map.remove(key.as_str()), model.remove(key.as_str())),
+            Op::Get(key) => {
+                assert_eq!(map.get(key.as_str()), model.get(key.as_str()));
+                assert_eq!(map.contains_key(&key), model.contains_key(&key));
+            }
+            Op::GetMut(key, value) => {
+                let expected = model.get_mut(key.as_str()).map(|v| std::mem::replace(v, value));
+                assert_eq!(map.get_mut(key.as_str()).map(|v| std::mem::replace(v, value)), expected);
+            }
+            Op::Entry(key, value) => {
+                let expected = *model
+                    .entry(key.clone())
+                    .and_modify(|v| *v = v.wrapping_add(value))
+                    .or_insert(value);
+                let actual = *map
+                    .entry(key)
+                    .and_modify(|v| *v = v.wrapping_add(value))
+                    .or_insert(value);
+                assert_eq!(actual, expected);
+            }
+            Op::Retain(mask) => {
+                map.retain(|_, v| *v & mask == 0);
+                model.retain(|_, v| *v & mask == 0);
+            }
+            Op::Extend(entries) => {
+                map.extend(entries.iter().cloned());
+                model.extend(entries);
+            }
+            Op::Clear => {
+                map.clear();
+                model.clear();
+            }
+            Op::Reserve(additional) => map.reserve(additional as usize),
+            Op::ShrinkToFit => map.shrink_to_fit(),
+            Op::Clone => {
+                let cloned = map.clone();
+                assert_same(&cloned, &model);
+                map = cloned;
+            }
+        }
+        assert_eq!(map.len(), model.len());
+    }
+    assert_same(&map, &model);
+});
@@ -2,7 +2,7 @@ use crate::convert::*;
 use crate::operations::*;
 use crate::random_state::PI;
 use crate::RandomState;
-use core::hash::Hasher;
+use core::hash::{BuildHasher, Hash, Hasher};
 
 /// A `Hasher` for hashing an arbitrary stream of bytes.
 ///
@@ -94,7 +94,6 @@ impl AHasher {
     }
 
     #[inline]
-    #[cfg(specialize)]
     fn short_finish(&self) -> u64 {
         let combined = aesenc(self.sum, self.enc);
         let result: [u64; 2] = aesdec(combined, combined).convert();
@@ -214,14 +213,104 @@ impl Hasher for AHasher {
     }
 }
 
-#[cfg(specialize)]
-pub(crate) struct AHasherU64 {
+/// A [BuildHasher] for the AES [AHasher], which is exported as `ahash::AesHasher`.
+///
+/// This holds the same keys as [RandomState] and has the same constructors, but always builds the AES
+/// hasher rather than the one selected for the target. It is only available on targets with AES instructions, where
+/// its hashers produce the same hashes as those built by [RandomState::build_hasher].
+///
+/// Note that [RandomState::hash_one] may differ: on nightly it uses specialized hashers for some types (such as
+/// integers and owned strings) which [AesRandomState::hash_one] does not.
+#[derive(Clone, Debug)]
+pub struct AesRandomState {
+    state: RandomState,
+}
+
+impl AesRandomState {
+    /// Creates a new `AesRandomState` using random keys. See [RandomState::new].
+    #[inline]
+    pub fn new() -> AesRandomState {
+        Self::with_random_state(RandomState::new())
+    }
+
+    /// Creates a new `AesRandomState` with fixed keys. See [RandomState::with_seeds].
+    #[inline]
+    pub const fn with_seeds(k0: u64, k1: u64, k2: u64, k3: u64) -> AesRandomState {
+        Self::with_random_state(RandomState::with_seeds(k0, k1, k2, k3))
+    }
+
+    /// Creates a new `AesRandomState` using the keys of the provided [RandomState].
+    #[inline]
+    pub const fn with_random_state(state: RandomState) -> AesRandomState {
+        AesRandomState { state }
+    }
+
+    /// Returns the underlying [RandomState].
+    #[inline]
+    pub fn random_state(&self) -> &RandomState {
+        &self.state
+    }
+
+    /// Calculates the hash of a single value with a hasher from this `AesRandomState`. This always uses
+    /// [Hash::hash], so it matches hashing with [BuildHasher::build_hasher] rather than [RandomState::hash_one].
+    #[inline]
+    pub fn hash_one<T: Hash>(&self, x: T) -> u64 {
+        let mut hasher = self.build_hasher();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Creates an instance using keys obtained from the random number generator. This is available under the same
+/// conditions as the `Default` impl of [RandomState].
+#[cfg(any(feature = "compile-time-rng", feature = "runtime-rng", feature = "no-rng"))]
+impl Default for AesRandomState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RandomState> for AesRandomState {
+    #[inline]
+    fn from(state: RandomState) -> Self {
+        Self::with_random_state(state)
+    }
+}
+
+impl BuildHasher for AesRandomState {
+    type Hasher = AHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> AHasher {
+        AHasher::from_random_state(&self.state)
+    }
+}
+
+pub struct AHasherU64 {
     pub(crate) buffer: u64,
     pub(crate) pad: u64,
+    pub(crate) extra_keys: [u64; 2],
+}
+
+impl AHasherU64 {
+    /// Mixes in a write which the `Hash` impls of the types this hasher is selected for never make, by passing it to
+    /// an [AHasher] keyed by the current state. This keeps other `Hash` impls correct, if not fast.
+    #[cold]
+    fn write_generic(&mut self, write: impl FnOnce(&mut AHasher)) {
+        let [k2, k3] = self.extra_keys;
+        let mut hasher = AHasher::from_random_state(&RandomState {
+            k0: self.pad,
+            k1: self.buffer,
+            k2,
+            k3,
+        });
+        write(&mut hasher);
+        self.buffer = hasher.finish();
+    }
 }
 
 /// A specialized hasher for only primitives under 64 bits.
-#[cfg(specialize)]
 impl Hasher for AHasherU64 {
     #[inline]
     fn finish(&self) -> u64 {
@@ -229,8 +318,8 @@ impl Hasher for AHasherU64 {
     }
 
     #[inline]
-    fn write(&mut self, _bytes: &[u8]) {
-        unreachable!("Specialized hasher was called with a different type of object")
+    fn write(&mut self, bytes: &[u8]) {
+        self.write_generic(|h| h.write(bytes));
     }
 
     #[inline]
@@ -254,21 +343,19 @@ impl Hasher for AHasherU64 {
     }
 
     #[inline]
-    fn write_u128(&mut self, _i: u128) {
-        unreachable!("Specialized hasher was called with a different type of object")
+    fn write_u128(&mut self, i: u128) {
+        self.write_generic(|h| h.write_u128(i));
     }
 
     #[inline]
-    fn write_usize(&mut self, _i: usize) {
-        unreachable!("Specialized hasher was called with a different type of object")
+    fn write_usize(&mut self, i: usize) {
+        self.write_generic(|h| h.write_usize(i));
     }
 }
 
-#[cfg(specialize)]
-pub(crate) struct AHasherFixed(pub AHasher);
+pub struct AHasherFixed(pub(crate) AHasher);
 
 /// A specialized hasher for fixed size primitives larger than 64 bits.
-#[cfg(specialize)]
 impl Hasher for AHasherFixed {
     #[inline]
     fn finish(&self) -> u64 {
@@ -311,21 +398,49 @@ impl Hasher for AHasherFixed {
     }
 }
 
-#[cfg(specialize)]
-pub(crate) struct AHasherStr(pub AHasher);
+/// The second field records the last call, so that the terminator of a `str` and the length prefix of a slice can be
+/// skipped. A length prefix is held back, and dropped if the next write has that length.
+pub struct AHasherStr(pub(crate) AHasher, pub(crate) LastWrite);
+
+impl AHasherStr {
+    /// Passes an integer write, preceded by any held back length prefix, to the generic path. This is followed by the
+    /// same rounds as a string, as `finish` only reads `enc`.
+    #[inline]
+    fn write_generic(&mut self, write: impl FnOnce(&mut AHasher)) {
+        if let LastWrite::LenPrefix(len) = self.1 {
+            self.0.write_usize(len);
+        }
+        self.1 = LastWrite::Other;
+        write(&mut self.0);
+        self.0.enc = aesenc(self.0.sum, self.0.enc);
+        self.0.enc = aesdec(aesdec(self.0.enc, self.0.key), self.0.enc);
+    }
+}
 
 /// A specialized hasher for strings
-/// Note that the other types don't panic because the hash impl for String tacks on an unneeded call. (As does vec)
-#[cfg(specialize)]
+/// Integer writes other than the terminator of a `str` and the length prefix of a slice go through the generic path.
 impl Hasher for AHasherStr {
     #[inline]
     fn finish(&self) -> u64 {
-        let result: [u64; 2] = self.0.enc.convert();
+        let enc = match self.1 {
+            LastWrite::LenPrefix(len) => {
+                let mut hasher = AHasherStr(self.0.clone(), LastWrite::Other);
+                hasher.write_generic(|h| h.write_usize(len));
+                hasher.0.enc
+            }
+            _ => self.0.enc,
+        };
+        let result: [u64; 2] = enc.convert();
         result[0]
     }
 
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
+        if let LastWrite::LenPrefix(len) = self.1 {
+            if len != bytes.len() {
+                self.write_generic(|h| h.write_usize(len));
+            }
+        }
         if bytes.len() > 8 {
             self.0.write(bytes);
             self.0.enc = aesenc(self.0.sum, self.0.enc);
@@ -338,25 +453,45 @@ impl Hasher for AHasherStr {
             self.0.enc = aesenc(self.0.sum, self.0.enc);
             self.0.enc = aesdec(aesdec(self.0.enc, self.0.key), self.0.enc);
         }
+        self.1 = LastWrite::Bytes;
     }
 
     #[inline]
-    fn write_u8(&mut self, _i: u8) {}
+    fn write_u8(&mut self, i: u8) {
+        if i == 0xff && self.1 == LastWrite::Bytes {
+            self.1 = LastWrite::Other;
+        } else {
+            self.write_generic(|h| h.write_u8(i));
+        }
+    }
 
     #[inline]
-    fn write_u16(&mut self, _i: u16) {}
+    fn write_u16(&mut self, i: u16) {
+        self.write_generic(|h| h.write_u16(i));
+    }
 
     #[inline]
-    fn write_u32(&mut self, _i: u32) {}
+    fn write_u32(&mut self, i: u32) {
+        self.write_generic(|h| h.write_u32(i));
+    }
 
     #[inline]
-    fn write_u64(&mut self, _i: u64) {}
+    fn write_u64(&mut self, i: u64) {
+        self.write_generic(|h| h.write_u64(i));
+    }
 
     #[inline]
-    fn write_u128(&mut self, _i: u128) {}
+    fn write_u128(&mut self, i: u128) {
+        self.write_generic(|h| h.write_u128(i));
+    }
 
     #[inline]
-    fn write_usize(&mut self, _i: usize) {}
+    fn write_usize(&mut self, i: usize) {
+        if let LastWrite::LenPrefix(len) = self.1 {
+            self.write_generic(|h| h.write_usize(len));
+        }
+        self.1 = LastWrite::LenPrefix(i);
+    }
 }
 
 #[cfg(test)]
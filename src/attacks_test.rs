@@ -0,0 +1,277 @@
+//! Attempts the published classes of attack on keyed hashes and asserts that they fail.
+//!
+//! Each attack produces pairs of inputs that collide, or differ by a fixed amount, for hashes that mix their input
+//! without enough dependence on the key. For every pair the hashes must differ under every key, and how they differ
+//! must itself depend on the key, otherwise an attacker could predict which bucket of a table the inputs land in.
+use crate::operations::read_small;
+use core::hash::Hasher;
+
+const KEYS: [(u128, u128); 4] = [
+    (0, 0),
+    (1, 2),
+    (
+        0x0123_4567_89ab_cdef_fedc_ba98_7654_3210,
+        0x0f1e_2d3c_4b5a_6978_8796_a5b4_c3d2_e1f0,
+    ),
+    (u128::MAX, 0x8000_0000_0000_0000_8000_0000_0000_0000),
+];
+
+fn hash<T: Hasher>(mut hasher: T, write: impl Fn(&mut T)) -> u64 {
+    write(&mut hasher);
+    hasher.finish()
+}
+
+/// Asserts that `a` and `b` never collide and that the difference between their hashes depends on the key.
+fn assert_key_dependent<T: Hasher>(
+    constructor: &impl Fn(u128, u128) -> T,
+    a: impl Fn(&mut T),
+    b: impl Fn(&mut T),
+    what: impl Fn() -> String,
+) {
+    let mut diffs = [0_u64; KEYS.len()];
+    for (diff, &(k1, k2)) in diffs.iter_mut().zip(KEYS.iter()) {
+        let (h1, h2) = (hash(constructor(k1, k2), &a), hash(constructor(k1, k2), &b));
+        assert_ne!(h1, h2, "{} collide for keys {:x}, {:x}", what(), k1, k2);
+        *diff = h1 ^ h2;
+    }
+    for i in 0..diffs.len() {
+        for j in i + 1..diffs.len() {
+            assert_ne!(diffs[i], diffs[j], "{} differ by {:x} for two keys", what(), diffs[i]);
+        }
+    }
+}
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i as u8).wrapping_mul(0x9b) ^ 0x5c).collect()
+}
+
+fn flip(data: &mut [u8], bit: usize) {
+    data[bit / 8] ^= 1 << (bit % 8);
+}
+
+/// Differences placed in two blocks so that the second cancels the first, as in the attacks on murmur and city hash.
+/// Every pair of single bit differences is tried in the same block, adjacent blocks, and blocks four apart (which are
+/// mixed into the same lane when inputs are processed 64 bytes at a time).
+fn test_differential_cancellation<T: Hasher>(constructor: impl Fn(u128, u128) -> T) {
+    for &len in [16, 24, 32, 48, 64, 80, 128].iter() {
+        let original = message(len);
+        let blocks = (len + 15) / 16;
+        for p in 0..blocks {
+            for &distance in [0, 1, 4].iter() {
+                let q = p + distance;
+                if q >= blocks {
+                    continue;
+                }
+                for i in p * 128..((p + 1) * 128).min(len * 8) {
+                    let start = if distance == 0 { i + 1 } else { q * 128 };
+                    for j in start..((q + 1) * 128).min(len * 8) {
+                        let mut changed = original.clone();
+                        flip(&mut changed, i);
+                        flip(&mut changed, j);
+                        assert_key_dependent(
+                            &constructor,
+                            |h| h.write(&original),
+                            |h| h.write(&changed),
+                            || format!("{} bytes with bits {} and {} flipped", len, i, j),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    // The same for separate integer writes, which each go through a single multiply in the fallback. (The words
+    // must not be digits of PI: those are the keys when the key is zero, which would make this a known key attack.)
+    for count in 2..=3 {
+        let words: Vec<u64> = (0..count)
+            .map(|i| 0x6a09_e667_f3bc_c908_u64.rotate_left(i * 17))
+            .collect();
+        for i in 0..64 * count as usize {
+            for j in i + 1..64 * count as usize {
+                let mut changed = words.clone();
+                changed[i / 64] ^= 1 << (i % 64);
+                changed[j / 64] ^= 1 << (j % 64);
+                assert_key_dependent(
+                    &constructor,
+                    |h| words.iter().for_each(|&w| h.write_u64(w)),
+                    |h| changed.iter().for_each(|&w| h.write_u64(w)),
+                    || format!("{} u64s with bits {} and {} flipped", count, i, j),
+                );
+            }
+        }
+    }
+    let words = [
+        0x6a09_e667_f3bc_c908_bb67_ae85_84ca_a73b_u128,
+        0x3c6e_f372_fe94_f82b_a54f_f53a_5f1d_36f1,
+    ];
+    for i in 0..128 {
+        for j in 128..256 {
+            let changed = [words[0] ^ (1 << i), words[1] ^ (1 << (j - 128))];
+            assert_key_dependent(
+                &constructor,
+                |h| words.iter().for_each(|&w| h.write_u128(w)),
+                |h| changed.iter().for_each(|&w| h.write_u128(w)),
+                || format!("two u128s with bits {} and {} flipped", i, j),
+            );
+        }
+    }
+}
+
+/// Appending data to an input, or moving where one write ends and the next begins, must not produce a predictable
+/// hash.
+fn test_length_extension_attack<T: Hasher>(constructor: impl Fn(u128, u128) -> T) {
+    for len in 0..=80 {
+        let original = message(len);
+        for extra in 1..=32 {
+            let mut extended = original.clone();
+            extended.resize(len + extra, 0);
+            assert_key_dependent(
+                &constructor,
+                |h| h.write(&original),
+                |h| h.write(&extended),
+                || format!("{} bytes extended by {} zeroes", len, extra),
+            );
+        }
+        assert_key_dependent(
+            &constructor,
+            |h| h.write(&original),
+            |h| {
+                h.write(&original);
+                h.write(&[]);
+            },
+            || format!("{} bytes followed by an empty write", len),
+        );
+        for split in 0..len {
+            assert_key_dependent(
+                &constructor,
+                |h| h.write(&original),
+                |h| {
+                    h.write(&original[..split]);
+                    h.write(&original[split..]);
+                },
+                || format!("{} bytes split at {}", len, split),
+            );
+            for other in split + 1..len {
+                assert_key_dependent(
+                    &constructor,
+                    |h| {
+                        h.write(&original[..split]);
+                        h.write(&original[split..]);
+                    },
+                    |h| {
+                        h.write(&original[..other]);
+                        h.write(&original[other..]);
+                    },
+                    || format!("{} bytes split at {} and at {}", len, split, other),
+                );
+            }
+        }
+    }
+}
+
+/// With all zero keys and all zero input there is nothing for the hash to mix, so it must not get stuck at a fixed
+/// point. (Keys are combined with constants before use, so zero keys do not mean a zero internal state.)
+fn test_zero_key_zero_input<T: Hasher>(constructor: impl Fn(u128, u128) -> T) {
+    let families = ["zero bytes", "zero u8 writes", "zero u64 writes", "zero u128 writes"];
+    for (family, name) in families.iter().enumerate() {
+        let mut seen = std::collections::HashMap::new();
+        for n in 0..=256 {
+            let value = hash(constructor(0, 0), |h| match family {
+                0 => h.write(&vec![0; n]),
+                1 => (0..n).for_each(|_| h.write_u8(0)),
+                2 => (0..n).for_each(|_| h.write_u64(0)),
+                _ => (0..n).for_each(|_| h.write_u128(0)),
+            });
+            assert_ne!(value, 0, "{} {} hash to zero", n, name);
+            if let Some(previous) = seen.insert(value, n) {
+                panic!("{} {} and {} {} both hash to {:x}", previous, name, n, name, value);
+            }
+        }
+    }
+}
+
+/// `read_small` reads inputs of up to 8 bytes as two overlapping values, so inputs of different lengths can read as
+/// the same values. Only the length, which is mixed in with the key, can tell them apart.
+fn test_short_input_collisions<T: Hasher>(constructor: impl Fn(u128, u128) -> T) {
+    let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    for a in 0..=255 {
+        pairs.push((vec![a], vec![a, 0, a]));
+        for b in 0..=255 {
+            pairs.push((vec![a, b], vec![a, b, b]));
+        }
+    }
+    for x in 0..4096_u32 {
+        let word = x.wrapping_mul(0x9e37_79b9).to_le_bytes();
+        pairs.push((word.to_vec(), [word, word].concat()));
+    }
+    for i in 0..=8 {
+        for j in i + 1..=8 {
+            pairs.push((vec![0; i], vec![0; j]));
+        }
+    }
+    for (a, b) in pairs.iter() {
+        assert_eq!(read_small(a), read_small(b));
+        assert_key_dependent(
+            &constructor,
+            |h| h.write(a),
+            |h| h.write(b),
+            || format!("{:?} and {:?}", a, b),
+        );
+    }
+}
+
+#[cfg(test)]
+mod fallback_tests {
+    use crate::attacks_test::*;
+    use crate::fallback_hash::*;
+
+    #[test]
+    fn fallback_differential_cancellation() {
+        test_differential_cancellation(AHasher::new_with_keys);
+    }
+
+    #[test]
+    fn fallback_length_extension_attack() {
+        test_length_extension_attack(AHasher::new_with_keys);
+    }
+
+    #[test]
+    fn fallback_zero_key_zero_input() {
+        test_zero_key_zero_input(AHasher::new_with_keys);
+    }
+
+    #[test]
+    fn fallback_short_input_collisions() {
+        test_short_input_collisions(AHasher::new_with_keys);
+    }
+}
+
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+    all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+    all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+))]
+#[cfg(test)]
+mod aes_tests {
+    use crate::aes_hash::*;
+    use crate::attacks_test::*;
+
+    #[test]
+    fn aes_differential_cancellation() {
+        test_differential_cancellation(AHasher::new_with_keys);
+    }
+
+    #[test]
+    fn aes_length_extension_attack() {
+        test_length_extension_attack(AHasher::new_with_keys);
+    }
+
+    #[test]
+    fn aes_zero_key_zero_input() {
+        test_zero_key_zero_input(AHasher::new_with_keys);
+    }
+
+    #[test]
+    fn aes_short_input_collisions() {
+        test_short_input_collisions(AHasher::new_with_keys);
+    }
+}
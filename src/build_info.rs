@@ -0,0 +1,160 @@
+//! Reports how this copy of aHash was built and where its keys come from.
+use core::fmt;
+
+/// The algorithm used by [AHasher](crate::AHasher) and [RandomState](crate::RandomState).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The hardware AES based algorithm, which is also available as `AesHasher` on such targets.
+    Aes,
+    /// The multiply based fallback algorithm. See [FallbackHasher](crate::FallbackHasher).
+    Fallback,
+}
+
+/// Where [RandomState::new](crate::RandomState::new) gets the randomness for its keys. See
+/// [RandomSource](crate::random_state::RandomSource).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RngSource {
+    /// Keys are derived from seeds obtained from the OS at runtime (the `runtime-rng` feature).
+    Runtime,
+    /// Keys are derived from seeds generated at compile time (the `compile-time-rng` feature).
+    CompileTime,
+    /// Keys are derived from fixed constants and the addresses of a counter. This is the weakest option.
+    Fixed,
+    /// A source installed with [set_random_source](crate::random_state::set_random_source).
+    Custom,
+}
+
+/// The Cargo features this crate can be built with, and whether each is enabled.
+const FEATURES: [(&str, bool); 17] = [
+    ("std", cfg!(feature = "std")),
+    ("runtime-rng", cfg!(feature = "runtime-rng")),
+    ("compile-time-rng", cfg!(feature = "compile-time-rng")),
+    ("no-rng", cfg!(feature = "no-rng")),
+    ("require-strong-rng", cfg!(feature = "require-strong-rng")),
+    ("atomic-polyfill", cfg!(feature = "atomic-polyfill")),
+    ("atfork", cfg!(feature = "atfork")),
+    ("rand_core", cfg!(feature = "rand_core")),
+    ("test-determinism", cfg!(feature = "test-determinism")),
+    ("trace", cfg!(feature = "trace")),
+    ("quality", cfg!(feature = "quality")),
+    ("rayon", cfg!(feature = "rayon")),
+    ("primitives", cfg!(feature = "primitives")),
+    ("derive", cfg!(feature = "derive")),
+    ("serde", cfg!(feature = "serde")),
+    ("nightly-arm-aes", cfg!(feature = "nightly-arm-aes")),
+    ("wasm-folded-multiply", cfg!(feature = "wasm-folded-multiply")),
+];
+
+/// Whether this copy of aHash was built with `cfg(folded_multiply)`. Cfgs set by a build script are not visible to
+/// other crates, so this lets them check it at compile time (as `ahash-wasm` does).
+#[doc(hidden)]
+pub const FOLDED_MULTIPLY: bool = cfg!(folded_multiply);
+
+/// A description of the aHash in use, returned by [build_info].
+///
+/// This is intended for diagnostics, such as logging at startup which backend a binary ended up with. Its `Display`
+/// impl writes it on a single line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// The algorithm [AHasher](crate::AHasher) uses.
+    pub backend: Backend,
+    /// Whether the fallback uses a 128 bit multiply (`cfg(folded_multiply)`, set by the build script on 64 bit
+    /// targets, and on wasm with the `wasm-folded-multiply` feature). If not it uses a slower and weaker emulation.
+    pub folded_multiply: bool,
+    /// Whether `hash_one` uses specialized hashers for primitive types (`cfg(specialize)`, set on nightly).
+    pub specialize: bool,
+    /// Where [RandomState::new](crate::RandomState::new) gets its randomness, as of the call to [build_info].
+    pub rng_source: RngSource,
+    /// Whether the process wide seeds used by [RandomState::with_seed](crate::RandomState::with_seed),
+    /// [AHasher::default](crate::AHasher) and the default [RngSource] are the publicly known digits of PI. If so keys
+    /// from those sources can be predicted by an attacker.
+    pub insecure_fixed_seeds: bool,
+}
+
+impl BuildInfo {
+    /// The Cargo features aHash was built with.
+    pub fn features(&self) -> impl Iterator<Item = &'static str> {
+        FEATURES.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| *name)
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "backend={:?} folded_multiply={} specialize={} rng_source={:?} insecure_fixed_seeds={} features=",
+            self.backend, self.folded_multiply, self.specialize, self.rng_source, self.insecure_fixed_seeds
+        )?;
+        for (i, feature) in self.features().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(feature)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns which hasher backend, compile time configuration and source of randomness this copy of aHash uses.
+///
+/// # Example
+/// ```
+/// use ahash::{build_info, Backend};
+///
+/// let info = build_info();
+/// if info.backend == Backend::Fallback || info.insecure_fixed_seeds {
+///     eprintln!("aHash is not running at full strength: {}", info);
+/// }
+/// ```
+pub fn build_info() -> BuildInfo {
+    let aes = cfg!(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "aes",
+            not(miri)
+        ),
+        all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+        all(
+            feature = "nightly-arm-aes",
+            target_arch = "arm",
+            target_feature = "aes",
+            not(miri)
+        ),
+    ));
+    BuildInfo {
+        backend: if aes { Backend::Aes } else { Backend::Fallback },
+        folded_multiply: FOLDED_MULTIPLY,
+        specialize: cfg!(specialize),
+        rng_source: crate::random_state::rng_source(),
+        insecure_fixed_seeds: crate::random_state::fixed_seeds_are_pi(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let info = build_info();
+        #[cfg(any(
+            all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+            all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+        ))]
+        assert_eq!(info.backend, Backend::Aes);
+        #[cfg(not(target_feature = "aes"))]
+        assert_eq!(info.backend, Backend::Fallback);
+        assert_eq!(info.folded_multiply, cfg!(folded_multiply));
+        #[cfg(all(feature = "runtime-rng", not(feature = "compile-time-rng")))]
+        assert_eq!(info.rng_source, RngSource::Runtime);
+        #[cfg(any(feature = "runtime-rng", feature = "compile-time-rng"))]
+        assert!(!info.insecure_fixed_seeds);
+        #[cfg(not(any(feature = "runtime-rng", feature = "compile-time-rng")))]
+        assert!(info.insecure_fixed_seeds);
+        assert_eq!(info.features().any(|f| f == "std"), cfg!(feature = "std"));
+        let line = info.to_string();
+        assert!(line.starts_with("backend="), "{}", line);
+        assert!(!line.contains('\n'));
+    }
+}
@@ -0,0 +1,360 @@
+//! Keyed content-defined chunking.
+//!
+//! A [Chunker] splits a stream of bytes into variable sized chunks whose boundaries depend only on the content
+//! near them. Inserting or removing bytes in the middle of the stream therefore only changes the chunks around
+//! the edit, which makes this useful for deduplication.
+//!
+//! Boundaries are found using a "gear" rolling hash (as in FastCDC). Unlike unkeyed implementations the gear table
+//! is derived from a [RandomState], so without knowing the keys an attacker cannot construct input which produces
+//! chunk boundaries of their choosing. Each chunk is fingerprinted with a 128 bit keyed hash.
+//!
+//! # Example
+//! ```
+//! use ahash::cdc::Chunker;
+//! use ahash::RandomState;
+//!
+//! let chunker = Chunker::new(&RandomState::with_seeds(1, 2, 3, 4), 256, 1024, 4096);
+//! let data = vec![7_u8; 10_000];
+//! let total: usize = chunker.chunks(&data).map(|c| c.length).sum();
+//! assert_eq!(total, data.len());
+//! ```
+use crate::RandomState;
+use core::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::vec::Vec;
+
+/// A single chunk found by a [Chunker].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chunk {
+    /// The position of the first byte of the chunk in the input.
+    pub offset: u64,
+    /// The number of bytes in the chunk.
+    pub length: usize,
+    /// A keyed 128 bit hash of the contents of the chunk.
+    pub fingerprint: u128,
+}
+
+/// Finds content-defined chunk boundaries using a keyed gear hash.
+///
+/// Two chunkers built from the same [RandomState] and sizes always produce the same chunks for the same input.
+#[derive(Clone)]
+pub struct Chunker {
+    gear: [u64; 256],
+    mask_small: u64,
+    mask_large: u64,
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    fingerprint_keys: [RandomState; 2],
+}
+
+impl Chunker {
+    /// Creates a chunker that produces chunks of at least `min_size` and at most `max_size` bytes, with boundaries
+    /// placed so that chunks are roughly `avg_size` bytes long. (Only the final chunk may be shorter than `min_size`.)
+    ///
+    /// The gear table and fingerprint keys are derived from `state`.
+    ///
+    /// # Panics
+    /// If `min_size` is zero or the sizes are not in the order `min_size <= avg_size <= max_size`.
+    pub fn new(state: &RandomState, min_size: usize, avg_size: usize, max_size: usize) -> Chunker {
+        assert!(min_size > 0, "min_size must be non-zero");
+        assert!(
+            min_size <= avg_size && avg_size <= max_size,
+            "Chunk sizes must satisfy min_size <= avg_size <= max_size"
+        );
+        // `write_u128` is used so that all four keys influence the result.
+        let derive = |index: u64| {
+            let mut hasher = state.build_hasher();
+            hasher.write_u128(index as u128);
+            hasher.finish()
+        };
+        let mut gear = [0_u64; 256];
+        for (i, g) in gear.iter_mut().enumerate() {
+            *g = derive(i as u64);
+        }
+        let fingerprint_keys = [
+            RandomState::with_seeds(derive(256), derive(257), derive(258), derive(259)),
+            RandomState::with_seeds(derive(260), derive(261), derive(262), derive(263)),
+        ];
+        // Normalized chunking: a boundary is harder to hit before the average size and easier after it.
+        let bits = (usize::BITS - avg_size.leading_zeros()).saturating_sub(1);
+        Chunker {
+            gear,
+            mask_small: high_bits(bits + 1),
+            mask_large: high_bits(bits.saturating_sub(1)),
+            min_size,
+            avg_size,
+            max_size,
+            fingerprint_keys,
+        }
+    }
+
+    /// The minimum size of a chunk.
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    /// The target average size of a chunk.
+    pub fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    /// The maximum size of a chunk.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the length of the first chunk in `data`.
+    ///
+    /// If `data` is shorter than `min_size` its full length is returned.
+    pub fn find_boundary(&self, data: &[u8]) -> usize {
+        if data.len() <= self.min_size {
+            return data.len();
+        }
+        let end = data.len().min(self.max_size);
+        let normal = end.min(self.avg_size);
+        let mut hash = 0_u64;
+        let mut i = self.min_size;
+        while i < normal {
+            hash = (hash << 1).wrapping_add(self.gear[data[i] as usize]);
+            if hash & self.mask_small == 0 {
+                return i + 1;
+            }
+            i += 1;
+        }
+        while i < end {
+            hash = (hash << 1).wrapping_add(self.gear[data[i] as usize]);
+            if hash & self.mask_large == 0 {
+                return i + 1;
+            }
+            i += 1;
+        }
+        end
+    }
+
+    /// Computes the keyed 128 bit fingerprint of a chunk's contents.
+    pub fn fingerprint(&self, chunk: &[u8]) -> u128 {
+        let hash = |state: &RandomState| {
+            let mut hasher = state.build_hasher();
+            hasher.write(chunk);
+            hasher.finish()
+        };
+        ((hash(&self.fingerprint_keys[1]) as u128) << 64) | hash(&self.fingerprint_keys[0]) as u128
+    }
+
+    /// Returns an iterator over the chunks of an in memory buffer.
+    pub fn chunks<'a>(&'a self, data: &'a [u8]) -> Chunks<'a> {
+        Chunks {
+            chunker: self,
+            data,
+            offset: 0,
+        }
+    }
+
+    /// Returns an iterator over the chunks read from `reader`. Each item contains the chunk and its contents.
+    ///
+    /// The chunks produced are identical to those from [Chunker::chunks] on the full contents of the reader.
+    pub fn chunk_reader<R: Read>(&self, reader: R) -> ReaderChunks<'_, R> {
+        ReaderChunks {
+            chunker: self,
+            reader,
+            buffer: Vec::with_capacity(2 * self.max_size),
+            start: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+}
+
+fn high_bits(count: u32) -> u64 {
+    match count {
+        0 => 0,
+        c if c >= 64 => u64::MAX,
+        c => !(u64::MAX >> c),
+    }
+}
+
+/// Iterator over the chunks of a slice. See [Chunker::chunks].
+pub struct Chunks<'a> {
+    chunker: &'a Chunker,
+    data: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Chunk;
+
+    fn next(&mut self) -> Option<Chunk> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let length = self.chunker.find_boundary(self.data);
+        let (chunk, rest) = self.data.split_at(length);
+        let result = Chunk {
+            offset: self.offset,
+            length,
+            fingerprint: self.chunker.fingerprint(chunk),
+        };
+        self.data = rest;
+        self.offset += length as u64;
+        Some(result)
+    }
+}
+
+/// Iterator over the chunks of a [Read]. See [Chunker::chunk_reader].
+pub struct ReaderChunks<'a, R> {
+    chunker: &'a Chunker,
+    reader: R,
+    buffer: Vec<u8>,
+    /// Where the unconsumed data in `buffer` begins.
+    start: usize,
+    offset: u64,
+    eof: bool,
+}
+
+impl<'a, R: Read> ReaderChunks<'a, R> {
+    fn fill(&mut self) -> io::Result<()> {
+        let max_size = self.chunker.max_size;
+        while !self.eof && self.buffer.len() - self.start < max_size {
+            // Consumed data is only discarded once there is at least `max_size` of it, so each byte is moved at most
+            // once and the buffer never grows beyond twice `max_size`.
+            if self.start >= max_size {
+                self.buffer.drain(..self.start);
+                self.start = 0;
+            }
+            let start = self.buffer.len();
+            self.buffer.resize(self.start + max_size, 0);
+            match self.reader.read(&mut self.buffer[start..]) {
+                Ok(0) => {
+                    self.buffer.truncate(start);
+                    self.eof = true;
+                }
+                Ok(read) => self.buffer.truncate(start + read),
+                Err(e) => {
+                    self.buffer.truncate(start);
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, R: Read> Iterator for ReaderChunks<'a, R> {
+    type Item = io::Result<(Chunk, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill() {
+            return Some(Err(e));
+        }
+        let available = &self.buffer[self.start..];
+        if available.is_empty() {
+            return None;
+        }
+        let length = self.chunker.find_boundary(available);
+        let data = available[..length].to_vec();
+        self.start += length;
+        let chunk = Chunk {
+            offset: self.offset,
+            length,
+            fingerprint: self.chunker.fingerprint(&data),
+        };
+        self.offset += length as u64;
+        Some(Ok((chunk, data)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn random_data(len: usize, seed: u64) -> Vec<u8> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        (0..len).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn test_chunks_cover_input_and_respect_sizes() {
+        let chunker = Chunker::new(&RandomState::with_seeds(1, 2, 3, 4), 512, 2048, 8192);
+        let data = random_data(200_000, 1);
+        let chunks: Vec<Chunk> = chunker.chunks(&data).collect();
+        let mut offset = 0;
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.offset, offset);
+            assert!(chunk.length <= 8192);
+            if i != chunks.len() - 1 {
+                assert!(chunk.length >= 512);
+            }
+            offset += chunk.length as u64;
+        }
+        assert_eq!(offset, data.len() as u64);
+        let avg = data.len() / chunks.len();
+        assert!(avg > 1024 && avg < 4096, "Average chunk size {}", avg);
+    }
+
+    /// A reader which returns at most 37 bytes per call.
+    struct ShortReads<'a>(&'a [u8]);
+
+    impl<'a> Read for ShortReads<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(37);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    fn check_reader<R: Read>(chunker: &Chunker, data: &[u8], reader: R) {
+        let expected: Vec<Chunk> = chunker.chunks(data).collect();
+        let mut actual = Vec::new();
+        for item in chunker.chunk_reader(reader) {
+            let (chunk, contents) = item.unwrap();
+            let start = chunk.offset as usize;
+            assert_eq!(&data[start..start + chunk.length], &contents[..]);
+            actual.push(chunk);
+        }
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_reader_matches_slice() {
+        let chunker = Chunker::new(&RandomState::with_seeds(5, 6, 7, 8), 64, 256, 1024);
+        let data = random_data(50_000, 2);
+        check_reader(&chunker, &data, &data[..]);
+        check_reader(&chunker, &data, ShortReads(&data));
+    }
+
+    #[test]
+    fn test_boundaries_resynchronize_after_insert() {
+        let chunker = Chunker::new(&RandomState::with_seeds(1, 2, 3, 4), 128, 512, 2048);
+        let data = random_data(100_000, 3);
+        let mut edited = data.clone();
+        edited.splice(50_000..50_000, b"inserted".iter().cloned());
+        let original: Vec<u128> = chunker.chunks(&data).map(|c| c.fingerprint).collect();
+        let modified: Vec<u128> = chunker.chunks(&edited).map(|c| c.fingerprint).collect();
+        let shared = modified.iter().filter(|f| original.contains(f)).count();
+        assert!(shared + 4 >= original.len(), "{} of {} shared", shared, original.len());
+    }
+
+    #[test]
+    fn test_keys_affect_boundaries() {
+        let data = random_data(100_000, 4);
+        let a = Chunker::new(&RandomState::with_seeds(1, 2, 3, 4), 128, 512, 2048);
+        let b = Chunker::new(&RandomState::with_seeds(1, 2, 3, 5), 128, 512, 2048);
+        let a: Vec<usize> = a.chunks(&data).map(|c| c.length).collect();
+        let b: Vec<usize> = b.chunks(&data).map(|c| c.length).collect();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let chunker = Chunker::new(&RandomState::with_seeds(1, 2, 3, 4), 16, 64, 256);
+        let a = chunker.fingerprint(b"foo");
+        assert_eq!(a, chunker.fingerprint(b"foo"));
+        assert_ne!(a, chunker.fingerprint(b"bar"));
+        assert_ne!(a as u64, (a >> 64) as u64);
+    }
+}
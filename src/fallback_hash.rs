@@ -1,10 +1,11 @@
 use crate::convert::*;
 use crate::operations::folded_multiply;
 use crate::operations::read_small;
+use crate::operations::LastWrite;
 use crate::operations::MULTIPLE;
 use crate::random_state::PI;
 use crate::RandomState;
-use core::hash::Hasher;
+use core::hash::{BuildHasher, Hash, Hasher};
 
 const ROT: u32 = 23; //17
 
@@ -115,7 +116,6 @@ impl AHasher {
     }
 
     #[inline]
-    #[cfg(specialize)]
     fn short_finish(&self) -> u64 {
         folded_multiply(self.buffer, self.pad)
     }
@@ -199,14 +199,118 @@ impl Hasher for AHasher {
     }
 }
 
-#[cfg(specialize)]
-pub(crate) struct AHasherU64 {
+/// A [BuildHasher] for the fallback [AHasher], which is exported as `ahash::FallbackHasher`.
+///
+/// This holds the same keys as [RandomState] and has the same constructors, but always builds the fallback
+/// hasher rather than the one selected for the target. On targets without AES its hashers produce the same hashes as
+/// those built by [RandomState::build_hasher]; on targets with AES it makes the fallback available for comparison.
+///
+/// Note that [RandomState::hash_one] may differ: on nightly it uses specialized hashers for some types (such as
+/// integers and owned strings) which [FallbackRandomState::hash_one] does not.
+#[derive(Clone, Debug)]
+pub struct FallbackRandomState {
+    state: RandomState,
+}
+
+impl FallbackRandomState {
+    /// Creates a new `FallbackRandomState` using random keys. See [RandomState::new].
+    #[inline]
+    pub fn new() -> FallbackRandomState {
+        Self::with_random_state(RandomState::new())
+    }
+
+    /// Creates a new `FallbackRandomState` with fixed keys. See [RandomState::with_seeds].
+    #[inline]
+    pub const fn with_seeds(k0: u64, k1: u64, k2: u64, k3: u64) -> FallbackRandomState {
+        Self::with_random_state(RandomState::with_seeds(k0, k1, k2, k3))
+    }
+
+    /// Creates a new `FallbackRandomState` using the keys of the provided [RandomState].
+    #[inline]
+    pub const fn with_random_state(state: RandomState) -> FallbackRandomState {
+        FallbackRandomState { state }
+    }
+
+    /// Returns the underlying [RandomState].
+    #[inline]
+    pub fn random_state(&self) -> &RandomState {
+        &self.state
+    }
+
+    /// Calculates the hash of a single value with a hasher from this `FallbackRandomState`. This always uses
+    /// [Hash::hash], so it matches hashing with [BuildHasher::build_hasher] rather than [RandomState::hash_one].
+    #[inline]
+    pub fn hash_one<T: Hash>(&self, x: T) -> u64 {
+        let mut hasher = self.build_hasher();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Creates an instance using keys obtained from the random number generator. This is available under the same
+/// conditions as the `Default` impl of [RandomState].
+#[cfg(any(feature = "compile-time-rng", feature = "runtime-rng", feature = "no-rng"))]
+impl Default for FallbackRandomState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RandomState> for FallbackRandomState {
+    #[inline]
+    fn from(state: RandomState) -> Self {
+        Self::with_random_state(state)
+    }
+}
+
+impl BuildHasher for FallbackRandomState {
+    type Hasher = AHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> AHasher {
+        AHasher::from_random_state(&self.state)
+    }
+}
+
+// Where the fallback is the default `AHasher`, this is implemented in lib.rs.
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+    all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+    all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+))]
+impl Default for AHasher {
+    /// Constructs a new fallback hasher with the same fixed keys as `AHasher::default()`.
+    #[inline]
+    fn default() -> AHasher {
+        AHasher::from_random_state(&RandomState::with_fixed_keys())
+    }
+}
+
+pub struct AHasherU64 {
     pub(crate) buffer: u64,
     pub(crate) pad: u64,
+    pub(crate) extra_keys: [u64; 2],
+}
+
+impl AHasherU64 {
+    /// Mixes in a write which the `Hash` impls of the types this hasher is selected for never make, by passing it to
+    /// an [AHasher] keyed by the current state. This keeps other `Hash` impls correct, if not fast.
+    #[cold]
+    fn write_generic(&mut self, write: impl FnOnce(&mut AHasher)) {
+        let [k2, k3] = self.extra_keys;
+        let mut hasher = AHasher::from_random_state(&RandomState {
+            k0: self.pad,
+            k1: self.buffer,
+            k2,
+            k3,
+        });
+        write(&mut hasher);
+        self.buffer = hasher.finish();
+    }
 }
 
 /// A specialized hasher for only primitives under 64 bits.
-#[cfg(specialize)]
 impl Hasher for AHasherU64 {
     #[inline]
     fn finish(&self) -> u64 {
@@ -215,8 +319,8 @@ impl Hasher for AHasherU64 {
     }
 
     #[inline]
-    fn write(&mut self, _bytes: &[u8]) {
-        unreachable!("Specialized hasher was called with a different type of object")
+    fn write(&mut self, bytes: &[u8]) {
+        self.write_generic(|h| h.write(bytes));
     }
 
     #[inline]
@@ -240,21 +344,19 @@ impl Hasher for AHasherU64 {
     }
 
     #[inline]
-    fn write_u128(&mut self, _i: u128) {
-        unreachable!("Specialized hasher was called with a different type of object")
+    fn write_u128(&mut self, i: u128) {
+        self.write_generic(|h| h.write_u128(i));
     }
 
     #[inline]
-    fn write_usize(&mut self, _i: usize) {
-        unreachable!("Specialized hasher was called with a different type of object")
+    fn write_usize(&mut self, i: usize) {
+        self.write_generic(|h| h.write_usize(i));
     }
 }
 
-#[cfg(specialize)]
-pub(crate) struct AHasherFixed(pub AHasher);
+pub struct AHasherFixed(pub(crate) AHasher);
 
 /// A specialized hasher for fixed size primitives larger than 64 bits.
-#[cfg(specialize)]
 impl Hasher for AHasherFixed {
     #[inline]
     fn finish(&self) -> u64 {
@@ -297,20 +399,44 @@ impl Hasher for AHasherFixed {
     }
 }
 
-#[cfg(specialize)]
-pub(crate) struct AHasherStr(pub AHasher);
+/// The second field records the last call, so that the terminator of a `str` and the length prefix of a slice can be
+/// skipped. A length prefix is held back, and dropped if the next write has that length.
+pub struct AHasherStr(pub(crate) AHasher, pub(crate) LastWrite);
+
+impl AHasherStr {
+    /// Passes an integer write, preceded by any held back length prefix, to the generic path.
+    #[inline]
+    fn write_generic(&mut self, write: impl FnOnce(&mut AHasher)) {
+        if let LastWrite::LenPrefix(len) = self.1 {
+            self.0.write_usize(len);
+        }
+        self.1 = LastWrite::Other;
+        write(&mut self.0);
+    }
+}
 
 /// A specialized hasher for a single string
-/// Note that the other types don't panic because the hash impl for String tacks on an unneeded call. (As does vec)
-#[cfg(specialize)]
+/// Integer writes other than the terminator of a `str` and the length prefix of a slice go through the generic path.
 impl Hasher for AHasherStr {
     #[inline]
     fn finish(&self) -> u64 {
-        self.0.finish()
+        match self.1 {
+            LastWrite::LenPrefix(len) => {
+                let mut hasher = self.0.clone();
+                hasher.write_usize(len);
+                hasher.finish()
+            }
+            _ => self.0.finish(),
+        }
     }
 
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
+        if let LastWrite::LenPrefix(len) = self.1 {
+            if len != bytes.len() {
+                self.write_generic(|h| h.write_usize(len));
+            }
+        }
         if bytes.len() > 8 {
             self.0.write(bytes)
         } else {
@@ -318,25 +444,45 @@ impl Hasher for AHasherStr {
             self.0.buffer = folded_multiply(value[0] ^ self.0.buffer, value[1] ^ self.0.extra_keys[1]);
             self.0.pad = self.0.pad.wrapping_add(bytes.len() as u64);
         }
+        self.1 = LastWrite::Bytes;
     }
 
     #[inline]
-    fn write_u8(&mut self, _i: u8) {}
+    fn write_u8(&mut self, i: u8) {
+        if i == 0xff && self.1 == LastWrite::Bytes {
+            self.1 = LastWrite::Other;
+        } else {
+            self.write_generic(|h| h.write_u8(i));
+        }
+    }
 
     #[inline]
-    fn write_u16(&mut self, _i: u16) {}
+    fn write_u16(&mut self, i: u16) {
+        self.write_generic(|h| h.write_u16(i));
+    }
 
     #[inline]
-    fn write_u32(&mut self, _i: u32) {}
+    fn write_u32(&mut self, i: u32) {
+        self.write_generic(|h| h.write_u32(i));
+    }
 
     #[inline]
-    fn write_u64(&mut self, _i: u64) {}
+    fn write_u64(&mut self, i: u64) {
+        self.write_generic(|h| h.write_u64(i));
+    }
 
     #[inline]
-    fn write_u128(&mut self, _i: u128) {}
+    fn write_u128(&mut self, i: u128) {
+        self.write_generic(|h| h.write_u128(i));
+    }
 
     #[inline]
-    fn write_usize(&mut self, _i: usize) {}
+    fn write_usize(&mut self, i: usize) {
+        if let LastWrite::LenPrefix(len) = self.1 {
+            self.write_generic(|h| h.write_usize(len));
+        }
+        self.1 = LastWrite::LenPrefix(i);
+    }
 }
 
 #[cfg(test)]
@@ -357,4 +503,39 @@ mod tests {
         let result2: [u8; 8] = result2.convert();
         assert_ne!(hex::encode(result), hex::encode(result2));
     }
+
+    /// Checks the vectors shared with the `ahash-wasm` tests, so that hashes computed in wasm match native ones.
+    #[cfg(folded_multiply)]
+    #[test]
+    fn test_golden_vectors() {
+        use core::hash::Hash;
+
+        let vectors = include_str!("../tests/fallback_golden_vectors.txt");
+        let mut count = 0;
+        for line in vectors.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let seeds: Vec<u64> = fields[1..5].iter().map(|s| s.parse().unwrap()).collect();
+            let state = RandomState::with_seeds(seeds[0], seeds[1], seeds[2], seeds[3]);
+            let mut hasher = AHasher::from_random_state(&state);
+            let input = fields[5];
+            let bytes = || {
+                if input == "-" {
+                    vec![]
+                } else {
+                    hex::decode(input).unwrap()
+                }
+            };
+            match fields[0] {
+                "str" => String::from_utf8(bytes()).unwrap().hash(&mut hasher),
+                "bytes" => bytes()[..].hash(&mut hasher),
+                "u64" => input.parse::<u64>().unwrap().hash(&mut hasher),
+                "u128" => input.parse::<u128>().unwrap().hash(&mut hasher),
+                kind => panic!("Unknown kind {}", kind),
+            }
+            let expected = u64::from_str_radix(fields[6], 16).unwrap();
+            assert_eq!(hasher.finish(), expected, "{}", line);
+            count += 1;
+        }
+        assert!(count > 0);
+    }
 }
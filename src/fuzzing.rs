@@ -0,0 +1,36 @@
+//! Internals used by the fuzz targets in `fuzz/`. This is only compiled with `--cfg fuzzing` (which `cargo fuzz`
+//! sets) and is not part of the public API.
+
+/// Whether `RandomState::hash_one` uses specialized hashers for some types. If so, it only matches hashing with
+/// `build_hasher` for types without a specialization.
+pub const SPECIALIZED: bool = cfg!(specialize);
+
+/// The portable implementation of an AES encryption round.
+pub fn soft_aesenc(value: u128, key: u128) -> u128 {
+    crate::soft_aes::aesenc(value, key)
+}
+
+/// The portable implementation of an AES decryption round.
+pub fn soft_aesdec(value: u128, key: u128) -> u128 {
+    crate::soft_aes::aesdec(value, key)
+}
+
+/// The AES encryption round the hasher uses.
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+    all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+    all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+))]
+pub fn aesenc(value: u128, key: u128) -> u128 {
+    crate::operations::aesenc(value, key)
+}
+
+/// The AES decryption round the hasher uses.
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+    all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+    all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+))]
+pub fn aesdec(value: u128, key: u128) -> u128 {
+    crate::operations::aesdec(value, key)
+}
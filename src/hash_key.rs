@@ -0,0 +1,385 @@
+use crate::RandomState;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+        all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+        all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+    ))] {
+        use crate::aes_hash::{AHasherFixed, AHasherStr, AHasherU64};
+    } else {
+        use crate::fallback_hash::{AHasherFixed, AHasherStr, AHasherU64};
+    }
+}
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std as alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A key type which selects an optimized [Hasher](core::hash::Hasher) for itself.
+///
+/// On nightly Rust, aHash uses specialization so that [RandomState::hash_one] hashes integers with a single multiply
+/// and strings with a single write. This trait provides the same fast paths on stable Rust for types which opt in.
+/// It is used by [RandomState::hash_key] and by [AHashKeyState], which can be used as the hasher of a map.
+/// Without specialization, the generic [RandomState::hash_one] and maps using the default [RandomState] cannot pick
+/// a hasher based on the key's type, so on stable Rust the fast paths are only reached through these two.
+///
+/// It is implemented for the primitive integers, `char`, `bool`, string and byte slice types, and references to
+/// these. For a newtype around one of these it can be derived with `#[derive(AHashKey)]` (requires the `derive`
+/// feature), which uses the same hasher as the wrapped field:
+#[cfg_attr(
+    feature = "derive",
+    doc = r##"
+```
+use ahash::{AHashKey, RandomState};
+
+#[derive(Hash, PartialEq, Eq, AHashKey)]
+struct UserId(u64);
+
+let state = RandomState::with_seeds(1, 2, 3, 4);
+assert_ne!(state.hash_key(&UserId(1)), state.hash_key(&UserId(2)));
+```
+"##
+)]
+/// The optimized hashers are only fast for the calls made by the `Hash` impls of these types. Any other calls fall
+/// back to the generic path, so every [Hash] implementation gives correct results, but one that makes different calls
+/// than the type its [AHashKey::Hasher] comes from (unlike `#[derive(Hash)]` on a struct with a single field) may be
+/// slower than using [RandomState] directly.
+pub trait AHashKey: Hash {
+    /// The hasher used for this type. (This is an implementation detail of aHash and cannot be named.)
+    type Hasher: core::hash::Hasher;
+
+    /// Creates a hasher for this type keyed by `state`.
+    fn build_key_hasher(state: &RandomState) -> Self::Hasher;
+}
+
+macro_rules! key_impl {
+    ($hasher:ty, $build:ident; $($typ:ty),*) => {
+        $(
+            impl AHashKey for $typ {
+                type Hasher = $hasher;
+
+                #[inline]
+                fn build_key_hasher(state: &RandomState) -> Self::Hasher {
+                    state.$build()
+                }
+            }
+        )*
+    };
+}
+
+key_impl!(AHasherU64, build_u64_hasher; u8, u16, u32, u64, i8, i16, i32, i64, char, bool);
+key_impl!(AHasherFixed, build_fixed_length_hasher; u128, i128, usize, isize);
+key_impl!(AHasherStr, build_str_hasher; str, String, Box<str>, [u8], Vec<u8>, Box<[u8]>);
+
+impl<T: AHashKey + ?Sized> AHashKey for &T {
+    type Hasher = T::Hasher;
+
+    #[inline]
+    fn build_key_hasher(state: &RandomState) -> Self::Hasher {
+        T::build_key_hasher(state)
+    }
+}
+
+impl<T: AHashKey + ?Sized> AHashKey for &mut T {
+    type Hasher = T::Hasher;
+
+    #[inline]
+    fn build_key_hasher(state: &RandomState) -> Self::Hasher {
+        T::build_key_hasher(state)
+    }
+}
+
+impl RandomState {
+    /// Calculates the hash of a single key using the optimized hasher for its type.
+    ///
+    /// This is the stable Rust equivalent of the specialized [RandomState::hash_one].
+    /// See [AHashKey] for the types this supports.
+    #[inline]
+    pub fn hash_key<K: AHashKey + ?Sized>(&self, key: &K) -> u64 {
+        use core::hash::Hasher;
+
+        let mut hasher = K::build_key_hasher(self);
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A [BuildHasher] for maps with keys of type `K` which uses the optimized hasher selected by [AHashKey].
+///
+/// Lookups by a borrowed form of the key (such as `&str` for a `String` key) use the same hasher, so they remain
+/// consistent with the stored keys.
+#[cfg_attr(
+    feature = "std",
+    doc = r##" # Examples
+```
+use ahash::{AHashKeyState, AHashMap};
+
+let mut map: AHashMap<String, u32, AHashKeyState<String>> = AHashMap::with_key_hasher();
+map.insert("foo".to_string(), 1);
+assert_eq!(map.get("foo"), Some(&1));
+```
+    "##
+)]
+pub struct AHashKeyState<K: ?Sized> {
+    state: RandomState,
+    _key: PhantomData<fn(&K)>,
+}
+
+impl<K: ?Sized> AHashKeyState<K> {
+    /// Creates a new `AHashKeyState` using random keys. See [RandomState::new].
+    #[inline]
+    pub fn new() -> AHashKeyState<K> {
+        Self::with_random_state(RandomState::new())
+    }
+
+    /// Creates a new `AHashKeyState` using the keys of the provided [RandomState].
+    #[inline]
+    pub const fn with_random_state(state: RandomState) -> AHashKeyState<K> {
+        AHashKeyState {
+            state,
+            _key: PhantomData,
+        }
+    }
+
+    /// Returns the underlying [RandomState].
+    #[inline]
+    pub fn random_state(&self) -> &RandomState {
+        &self.state
+    }
+}
+
+impl<K: ?Sized> Clone for AHashKeyState<K> {
+    fn clone(&self) -> Self {
+        Self::with_random_state(self.state.clone())
+    }
+}
+
+impl<K: ?Sized> fmt::Debug for AHashKeyState<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("AHashKeyState { .. }")
+    }
+}
+
+/// NOTE: For safety this trait impl is only available if either of the flags `runtime-rng` (on by default) or
+/// `compile-time-rng` are enabled. See the `Default` impl of [RandomState].
+#[cfg(any(feature = "compile-time-rng", feature = "runtime-rng", feature = "no-rng"))]
+impl<K: ?Sized> Default for AHashKeyState<K> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: AHashKey + ?Sized> BuildHasher for AHashKeyState<K> {
+    type Hasher = K::Hasher;
+
+    #[inline]
+    fn build_hasher(&self) -> K::Hasher {
+        K::build_key_hasher(&self.state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: AHashKey, V> crate::AHashMap<K, V, AHashKeyState<K>> {
+    /// Creates an empty map which hashes keys using [AHashKeyState].
+    pub fn with_key_hasher() -> Self {
+        Self::with_hasher(AHashKeyState::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: AHashKey> crate::AHashSet<T, AHashKeyState<T>> {
+    /// Creates an empty set which hashes items using [AHashKeyState].
+    pub fn with_key_hasher() -> Self {
+        Self::with_hasher(AHashKeyState::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AHashMap, AHashSet};
+
+    #[test]
+    fn test_hash_key_distinguishes_values() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        assert_ne!(state.hash_key(&1_u64), state.hash_key(&2_u64));
+        assert_ne!(state.hash_key(&1_u128), state.hash_key(&2_u128));
+        assert_ne!(state.hash_key("foo"), state.hash_key("bar"));
+        assert_ne!(state.hash_key("a"), state.hash_key("a\0"));
+        assert_eq!(state.hash_key("foo"), state.hash_key(&"foo".to_string()));
+        assert_eq!(state.hash_key(&&1_u32), state.hash_key(&1_u32));
+        assert_eq!(state.hash_key(&b"foo"[..]), state.hash_key(&b"foo".to_vec()));
+        assert_ne!(
+            state.hash_key("foo"),
+            RandomState::with_seeds(1, 2, 3, 5).hash_key("foo")
+        );
+    }
+
+    #[cfg(specialize)]
+    #[test]
+    fn test_hash_key_matches_specialized_hash_one() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        assert_eq!(state.hash_key(&7_u64), state.hash_one(7_u64));
+        assert_eq!(state.hash_key(&7_u128), state.hash_one(7_u128));
+        assert_eq!(state.hash_key("foo"), state.hash_one("foo".to_string()));
+    }
+
+    #[test]
+    fn test_maps_with_borrowed_lookups() {
+        let mut map: AHashMap<String, u32, AHashKeyState<String>> = AHashMap::with_key_hasher();
+        for i in 0..1000 {
+            map.insert(i.to_string(), i);
+        }
+        for i in 0..1000 {
+            assert_eq!(map.get(i.to_string().as_str()), Some(&i));
+        }
+        let mut set: AHashSet<u64, AHashKeyState<u64>> = AHashSet::with_key_hasher();
+        set.extend(0..1000);
+        assert!((0..1000).all(|i| set.contains(&i)));
+        assert!(!set.contains(&1000));
+        let mut map: std::collections::HashMap<Vec<u8>, u32, AHashKeyState<Vec<u8>>> = Default::default();
+        map.insert(b"foo".to_vec(), 1);
+        assert_eq!(map.get(&b"foo"[..]), Some(&1));
+    }
+
+    /// Uses the hasher of `K` but makes whichever calls `write` does.
+    struct Custom<K: ?Sized, F>(F, PhantomData<K>);
+
+    impl<K: ?Sized, F: Fn(&mut dyn core::hash::Hasher)> Hash for Custom<K, F> {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            (self.0)(state)
+        }
+    }
+
+    impl<K: AHashKey + ?Sized, F: Fn(&mut dyn core::hash::Hasher)> AHashKey for Custom<K, F> {
+        type Hasher = K::Hasher;
+
+        fn build_key_hasher(state: &RandomState) -> Self::Hasher {
+            K::build_key_hasher(state)
+        }
+    }
+
+    fn hash_custom<K: AHashKey + ?Sized>(write: impl Fn(&mut dyn core::hash::Hasher)) -> u64 {
+        RandomState::with_seeds(1, 2, 3, 4).hash_key(&Custom::<K, _>(write, PhantomData))
+    }
+
+    fn assert_all_distinct(hashes: &[u64]) {
+        for (i, a) in hashes.iter().enumerate() {
+            for b in &hashes[i + 1..] {
+                assert_ne!(a, b, "{:x?}", hashes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unexpected_writes_fall_back() {
+        assert_all_distinct(&[
+            hash_custom::<u64>(|h| h.write(b"foo")),
+            hash_custom::<u64>(|h| h.write(b"bar")),
+            hash_custom::<u64>(|h| h.write_u128(1)),
+            hash_custom::<u64>(|h| h.write_u128(2 << 64)),
+            hash_custom::<u64>(|h| h.write_usize(3)),
+            hash_custom::<u64>(|h| h.write_usize(4)),
+            hash_custom::<u64>(|h| {
+                h.write_u32(1);
+                h.write(b"foo");
+            }),
+            hash_custom::<u64>(|h| {
+                h.write_u32(2);
+                h.write(b"foo");
+            }),
+        ]);
+        assert_all_distinct(&[
+            hash_custom::<str>(|h| h.write(b"foo")),
+            hash_custom::<str>(|h| {
+                h.write(b"foo");
+                h.write_u8(1);
+            }),
+            hash_custom::<str>(|h| {
+                h.write(b"foo");
+                h.write_u32(3);
+            }),
+            hash_custom::<str>(|h| {
+                h.write(b"foo");
+                h.write_u64(2);
+            }),
+            hash_custom::<str>(|h| {
+                h.write_usize(1);
+                h.write(b"foo");
+            }),
+            hash_custom::<str>(|h| h.write_usize(3)),
+            hash_custom::<str>(|h| {
+                h.write(b"foo");
+                h.write_usize(5);
+            }),
+            hash_custom::<str>(|h| h.write_u128(1)),
+            hash_custom::<str>(|h| h.write_u128(1 << 64)),
+        ]);
+        // A length prefix matching the following write is redundant, so slices hash like strings.
+        assert_eq!(
+            hash_custom::<str>(|h| {
+                h.write_usize(3);
+                h.write(b"foo");
+            }),
+            hash_custom::<str>(|h| h.write(b"foo"))
+        );
+        assert_all_distinct(&[
+            hash_custom::<u128>(|h| h.write(b"foo")),
+            hash_custom::<u128>(|h| h.write_u8(1)),
+            hash_custom::<u128>(|h| h.write_u8(2)),
+        ]);
+    }
+
+    /// A `0xff` is only skipped when it is the terminator of a string, so it cannot be confused with other fields.
+    #[test]
+    fn test_0xff_after_integer_is_hashed() {
+        let pair = |a: u8, b: u8| {
+            hash_custom::<String>(move |h| {
+                h.write(b"id");
+                h.write_u8(a);
+                h.write_u8(b);
+            })
+        };
+        assert_ne!(pair(0xff, 7), pair(7, 0xff));
+        assert_ne!(pair(0xff, 0xff), pair(0xff, 0));
+        for seed in 0..16 {
+            let state = RandomState::with_seeds(seed, 2, 3, 4);
+            let hash = |a: u8, b: u8| {
+                state.hash_key(&Custom::<String, _>(
+                    move |h: &mut dyn core::hash::Hasher| {
+                        h.write(b"id");
+                        h.write_u8(a);
+                        h.write_u8(b);
+                    },
+                    PhantomData,
+                ))
+            };
+            assert_ne!(hash(0xff, 7), hash(7, 0xff));
+        }
+    }
+
+    /// Varying only the high bits of an integer written after a string must still change the low bits of the hash,
+    /// which is what a table uses to pick a bucket.
+    #[test]
+    fn test_integer_after_string_is_mixed() {
+        let low_bits: AHashSet<u64> = (0..64_u64)
+            .map(|i| {
+                hash_custom::<str>(move |h| {
+                    h.write(b"foo");
+                    h.write_u64(i << 56);
+                }) & 0xffff
+            })
+            .collect();
+        assert!(low_bits.len() > 60, "{}", low_bits.len());
+    }
+}
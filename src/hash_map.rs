@@ -2,7 +2,7 @@ use std::borrow::Borrow;
 use std::collections::hash_map::{IntoKeys, IntoValues};
 use std::collections::{hash_map, HashMap};
 use std::fmt::{self, Debug};
-use std::hash::{BuildHasher, Hash};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter::FromIterator;
 use std::ops::{Deref, DerefMut, Index};
 use std::panic::UnwindSafe;
@@ -314,6 +314,22 @@ where
 {
 }
 
+/// Hashes the entries of the map in a way that does not depend on their iteration order, so equal maps produce equal
+/// hashes even if they were built with different hashers. See [RandomState::hash_unordered].
+///
+/// The entries are hashed with keys derived from `state`, so whether two maps collide depends on its keys.
+impl<K, V, S> Hash for AHashMap<K, V, S>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.len());
+        let inner = RandomState::from_hasher(state);
+        state.write_u64(inner.hash_unordered(self.0.iter()));
+    }
+}
+
 impl<K, Q: ?Sized, V, S> Index<&Q> for AHashMap<K, V, S>
 where
     K: Eq + Hash + Borrow<Q>,
@@ -482,6 +498,26 @@ mod test {
         map.insert("Bar".to_string(), map.get("foo").unwrap().to_owned());
     }
 
+    #[test]
+    fn test_hash_is_order_independent() {
+        let mut a: AHashMap<u32, String> = AHashMap::with_capacity(4);
+        let mut b: AHashMap<u32, String> = AHashMap::with_capacity(1000);
+        for i in 0..100 {
+            a.insert(i, i.to_string());
+        }
+        for i in (0..100).rev() {
+            b.insert(i, i.to_string());
+        }
+        let build_hasher = RandomState::with_seeds(1, 2, 3, 4);
+        assert_eq!(build_hasher.hash_one(&a), build_hasher.hash_one(&b));
+        b.insert(0, "changed".to_string());
+        assert_ne!(build_hasher.hash_one(&a), build_hasher.hash_one(&b));
+
+        let mut maps: AHashMap<AHashMap<u32, String>, u32> = AHashMap::new();
+        maps.insert(a.clone(), 1);
+        assert_eq!(maps.get(&a), Some(&1));
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_serde() {
@@ -1,7 +1,7 @@
 use crate::RandomState;
 use std::collections::{hash_set, HashSet};
 use std::fmt::{self, Debug};
-use std::hash::{BuildHasher, Hash};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter::FromIterator;
 use std::ops::{BitAnd, BitOr, BitXor, Deref, DerefMut, Sub};
 
@@ -103,6 +103,21 @@ where
 {
 }
 
+/// Hashes the items of the set in a way that does not depend on their iteration order, so equal sets produce equal
+/// hashes even if they were built with different hashers. See [RandomState::hash_unordered].
+///
+/// The items are hashed with keys derived from `state`, so whether two sets collide depends on its keys.
+impl<T, S> Hash for AHashSet<T, S>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.len());
+        let inner = RandomState::from_hasher(state);
+        state.write_u64(inner.hash_unordered(self.0.iter()));
+    }
+}
+
 impl<T, S> BitOr<&AHashSet<T, S>> for &AHashSet<T, S>
 where
     T: Eq + Hash + Clone,
@@ -330,10 +345,53 @@ where
     }
 }
 
-#[cfg(all(test, feature = "serde"))]
+#[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_hash_is_order_independent() {
+        let a: AHashSet<u64> = (0..100).collect();
+        let b: AHashSet<u64> = (0..100).rev().collect();
+        let build_hasher = RandomState::with_seeds(1, 2, 3, 4);
+        assert_eq!(build_hasher.hash_one(&a), build_hasher.hash_one(&b));
+        let c: AHashSet<u64> = (1..101).collect();
+        assert_ne!(build_hasher.hash_one(&a), build_hasher.hash_one(&c));
+
+        let mut sets: AHashSet<AHashSet<u64>> = AHashSet::new();
+        sets.insert(a);
+        assert!(sets.contains(&b));
+        assert!(!sets.contains(&c));
+    }
+
+    /// A hasher with a fixed key which records the `u64`s written to it.
+    struct Recorder(u64, Vec<u64>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            self.0 ^ self.1.len() as u64
+        }
+
+        fn write(&mut self, _bytes: &[u8]) {}
+
+        fn write_u64(&mut self, i: u64) {
+            self.1.push(i);
+        }
+    }
+
+    #[test]
+    fn test_hash_depends_on_outer_keys() {
+        let set: AHashSet<u64> = (0..10).collect();
+        let contents = |key| {
+            let mut recorder = Recorder(key, Vec::new());
+            set.hash(&mut recorder);
+            *recorder.1.last().unwrap()
+        };
+        assert_eq!(contents(1), contents(1));
+        assert_ne!(contents(1), contents(2));
+    }
+
+    #[cfg(feature = "serde")]
     #[test]
     fn test_serde() {
         let mut set = AHashSet::new();
@@ -66,6 +66,21 @@ let hash = hash_builder.hash_one("Some Data");
 ```
 There are several constructors for [RandomState] with different ways to supply seeds.
 
+### Choosing the algorithm
+
+[AHasher] and [RandomState] use the AES based algorithm on targets with AES instructions and the fallback otherwise.
+To use a particular algorithm regardless, [FallbackHasher] and [FallbackRandomState] are always available, as are
+`AesHasher` and `AesRandomState` on targets with AES instructions. Given the same seeds, their hashers hash the same as
+those built by [RandomState] on targets where it uses the same algorithm. (Though on nightly [RandomState::hash_one]
+specializes some types, which their `hash_one` does not.)
+
+```
+use ahash::FallbackRandomState;
+
+let hash_builder = FallbackRandomState::with_seeds(1, 2, 3, 4);
+let hash = hash_builder.hash_one("Some Data");
+```
+
 # Convenience wrappers
 
 For convenience, both new-type wrappers and type aliases are provided.
@@ -100,9 +115,19 @@ Note the import of [HashMapExt]. This is needed for the constructor.
 #![cfg_attr(specialize, feature(min_specialization))]
 #![cfg_attr(feature = "nightly-arm-aes", feature(stdarch_arm_neon_intrinsics))]
 
+#[cfg(all(
+    feature = "require-strong-rng",
+    not(any(feature = "runtime-rng", feature = "compile-time-rng"))
+))]
+compile_error!(
+    "The `require-strong-rng` feature requires either `runtime-rng` or `compile-time-rng`, \
+    as otherwise aHash's keys are derived from fixed, publicly known seeds."
+);
+
 #[macro_use]
 mod convert;
 
+mod build_info;
 mod fallback_hash;
 
 cfg_if::cfg_if! {
@@ -113,15 +138,26 @@ cfg_if::cfg_if! {
         ))] {
         mod aes_hash;
         pub use crate::aes_hash::AHasher;
+        pub use crate::aes_hash::{AHasher as AesHasher, AesRandomState};
     } else {
         pub use crate::fallback_hash::AHasher;
     }
 }
 
+pub use crate::fallback_hash::{AHasher as FallbackHasher, FallbackRandomState};
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
+        pub mod cdc;
         mod hash_map;
         mod hash_set;
+        pub mod tree;
+        #[cfg(feature = "trace")]
+        pub mod trace;
+        #[cfg(feature = "quality")]
+        pub mod quality;
+        #[cfg(feature = "test-determinism")]
+        pub mod testing;
 
         pub use crate::hash_map::AHashMap;
         pub use crate::hash_set::AHashSet;
@@ -136,15 +172,36 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(test)]
+mod attacks_test;
 #[cfg(test)]
 mod hash_quality_test;
-
+#[cfg(all(test, not(feature = "quality")))]
+#[allow(dead_code)]
+mod quality;
+
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub mod fuzzing;
+mod hash_key;
 mod operations;
+#[cfg(feature = "primitives")]
+pub mod primitives;
 pub mod random_state;
+#[cfg(any(test, fuzzing))]
+mod soft_aes;
 mod specialize;
 
+#[doc(hidden)]
+pub use crate::build_info::FOLDED_MULTIPLY;
+pub use crate::build_info::{build_info, Backend, BuildInfo, RngSource};
+pub use crate::hash_key::{AHashKey, AHashKeyState};
 pub use crate::random_state::RandomState;
 
+/// Derive macros for [AHashKey] and field-packed [Hash](core::hash::Hash) implementations. See `ahash-derive`.
+#[cfg(feature = "derive")]
+pub use ahash_derive::{AHash, AHashKey};
+
 use core::hash::BuildHasher;
 
 #[cfg(feature = "std")]
@@ -337,6 +394,44 @@ mod test {
         let _ = AHasher::new_with_keys(1234, 5678);
     }
 
+    #[test]
+    fn test_explicit_algorithms() {
+        fn unspecialized<B: BuildHasher>(build_hasher: &B, value: &str) -> u64 {
+            let mut hasher = build_hasher.build_hasher();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+        let auto = unspecialized(&RandomState::with_seeds(1, 2, 3, 4), "test");
+        let fallback = FallbackRandomState::with_seeds(1, 2, 3, 4);
+        assert_eq!(fallback.hash_one("test"), unspecialized(&fallback, "test"));
+        assert_eq!(
+            unspecialized(&FallbackRandomState::from(RandomState::with_seeds(1, 2, 3, 4)), "test"),
+            unspecialized(&fallback, "test")
+        );
+        let mut hasher = FallbackHasher::default();
+        "test".hash(&mut hasher);
+        assert_eq!(
+            hasher.finish(),
+            unspecialized(
+                &FallbackRandomState::with_random_state(RandomState::with_fixed_keys()),
+                "test"
+            )
+        );
+        cfg_if::cfg_if! {
+            if #[cfg(any(
+                all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+                all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+                all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+            ))] {
+                let aes = AesRandomState::with_seeds(1, 2, 3, 4);
+                assert_eq!(unspecialized(&aes, "test"), auto);
+                assert_ne!(unspecialized(&fallback, "test"), auto);
+            } else {
+                assert_eq!(unspecialized(&fallback, "test"), auto);
+            }
+        }
+    }
+
     #[test]
     fn test_specialize_reference_hash() {
         let hasher_build = RandomState::with_seeds(0, 0, 0, 0);
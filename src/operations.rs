@@ -27,6 +27,18 @@ pub(crate) const fn folded_multiply(s: u64, by: u64) -> u64 {
     b1 ^ b2.swap_bytes()
 }
 
+/// The last call made to a string hasher. This decides whether the calls which the `Hash` impls of `str` and slices
+/// make around their bytes can be skipped, as the length of each write is mixed in anyway.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LastWrite {
+    /// Nothing, or an integer which has been hashed.
+    Other,
+    /// Bytes. A following `0xff` is the terminator written by `str`'s `Hash` impl.
+    Bytes,
+    /// A `usize` which has not been hashed yet, as it may be the length prefix of the following bytes.
+    LenPrefix(usize),
+}
+
 /// Given a small (less than 8 byte slice) returns the same data stored in two u32s.
 /// (order of and non-duplication of bytes is NOT guaranteed)
 #[inline(always)]
@@ -0,0 +1,175 @@
+//! The mixing functions used by aHash.
+//!
+//! These are exposed so that custom hash tables, RNGs and similar code can reuse the same building blocks as
+//! [AHasher](crate::AHasher) rather than copying them. They are all pure functions, and none of them are
+//! cryptographically secure.
+//!
+//! Like the hasher itself, the results are *not* portable. [mix64] depends on whether the target has a 128 bit
+//! multiply and [mix128] on whether it has AES instructions. On targets without these a portable fallback with
+//! the same properties is used. Results are stable for a given target within a minor version (which the tests check
+//! against recorded values for each of these configurations).
+//!
+//! # Example
+//! ```
+//! use ahash::primitives::{hash_u64, mix64};
+//!
+//! // A keyed hash for integer keys in a custom table.
+//! let keys = [0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344];
+//! let bucket = hash_u64(42, keys) as usize % 1024;
+//! assert!(bucket < 1024);
+//!
+//! // A simple counter based generator.
+//! let mut counter = 0_u64;
+//! let mut next = || {
+//!     counter = counter.wrapping_add(0x9e37_79b9_7f4a_7c15);
+//!     mix64(counter, counter ^ keys[0])
+//! };
+//! assert_ne!(next(), next());
+//! ```
+use crate::operations::{folded_multiply, MULTIPLE};
+
+/// Multiplies `a` by `b` to produce a 128 bit product and xors the high and low halves together.
+///
+/// Every bit of each input affects the output, with the middle bits of the inputs having the strongest effect.
+/// If `a` is zero the output is zero, and on targets with a 128 bit multiply the same is true of `b`, so callers should
+/// xor a key or constant into both arguments.
+#[inline(always)]
+pub const fn mix64(a: u64, b: u64) -> u64 {
+    folded_multiply(a, b)
+}
+
+/// Mixes `value` with `key` to produce a 128 bit result in which every output bit depends on every input bit.
+///
+/// When AES instructions are available this uses one AES encryption round followed by a decryption round (the same
+/// sequence the hasher uses to finish). Otherwise it uses a sequence of [mix64] operations.
+#[inline(always)]
+pub fn mix128(value: u128, key: u128) -> u128 {
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+            all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+            all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+        ))] {
+            use crate::operations::{aesdec, aesenc};
+            let combined = aesenc(value, key);
+            aesdec(combined, combined)
+        } else {
+            let (v0, v1) = (value as u64, (value >> 64) as u64);
+            let (k0, k1) = (key as u64, (key >> 64) as u64);
+            let high = folded_multiply(v0 ^ k0, MULTIPLE);
+            let high = folded_multiply(v1 ^ k1 ^ high, MULTIPLE);
+            let low = folded_multiply(v0 ^ k1 ^ high.rotate_left(32), MULTIPLE ^ k0);
+            ((high as u128) << 64) | low as u128
+        }
+    }
+}
+
+/// Hashes a single integer with a pair of keys.
+///
+/// This is the same function that [RandomState::hash_key](crate::RandomState::hash_key) uses for integer keys of
+/// up to 64 bits, so it costs just two [mix64] operations. For best results the keys should be random.
+#[inline(always)]
+pub const fn hash_u64(value: u64, keys: [u64; 2]) -> u64 {
+    folded_multiply(folded_multiply(value ^ keys[1], MULTIPLE), keys[0])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RandomState;
+
+    /// Checks that flipping each of the `in_bits` input bits changes close to half of the `out_bits` output bits.
+    fn assert_avalanche(name: &str, in_bits: u32, out_bits: u32, mut f: impl FnMut(u32) -> (u128, u128)) {
+        let mut total = 0;
+        for bit in 0..in_bits {
+            let (a, b) = f(bit);
+            total += (a ^ b).count_ones();
+        }
+        let average = total as f64 / in_bits as f64 / out_bits as f64;
+        assert!(
+            average > 0.4 && average < 0.6,
+            "{}: {} of the bits changed",
+            name,
+            average
+        );
+    }
+
+    #[test]
+    fn test_hash_u64_matches_hash_key() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        for value in [0_u64, 1, 42, u64::MAX, 0x1234_5678_9abc_def0] {
+            assert_eq!(hash_u64(value, [state.k0, state.k1]), state.hash_key(&value));
+        }
+    }
+
+    /// The outputs must not change within a minor version. There are separate values for targets with and without a
+    /// 128 bit multiply (`cfg(folded_multiply)`) and, for [mix128], with AES instructions.
+    #[test]
+    fn test_golden_values() {
+        #[cfg(folded_multiply)]
+        let (mix64_expected, hash_u64_expected) = (0x2317_228f_4816_5bb2, 0xbb1d_5bae_7f2b_1fb9);
+        #[cfg(not(folded_multiply))]
+        let (mix64_expected, hash_u64_expected) = (0xba77_06fc_8f97_788b, 0xc52b_6cf9_f444_00bf);
+        cfg_if::cfg_if! {
+            if #[cfg(any(
+                all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+                all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+                all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+            ))] {
+                let mix128_expected = 0xbb1a_95f8_efdf_54ef_ebf2_10d6_7900_eb09;
+            } else if #[cfg(folded_multiply)] {
+                let mix128_expected = 0x20fc_6c49_3c83_c29e_3e0c_e72b_4301_0376;
+            } else {
+                let mix128_expected = 0x2439_990a_0f90_6c75_5f32_d39d_766f_c58b;
+            }
+        }
+        assert_eq!(mix64(0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210), mix64_expected);
+        assert_eq!(
+            hash_u64(42, [0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344]),
+            hash_u64_expected
+        );
+        assert_eq!(
+            mix128(
+                0x0123_4567_89ab_cdef_fedc_ba98_7654_3210,
+                0x243f_6a88_85a3_08d3_1319_8a2e_0370_7344
+            ),
+            mix128_expected
+        );
+    }
+
+    #[test]
+    fn test_mix64() {
+        assert_eq!(mix64(0, 12345), 0);
+        #[cfg(folded_multiply)]
+        assert_eq!(mix64(12345, 0), 0);
+        let key = 0x243f_6a88_85a3_08d3;
+        assert_avalanche("mix64", 64, 64, |bit| {
+            let a = mix64(key, 0x1234_5678 ^ MULTIPLE);
+            let b = mix64(key, (0x1234_5678 ^ MULTIPLE) ^ (1 << bit));
+            (a as u128, b as u128)
+        });
+    }
+
+    #[test]
+    fn test_mix128_changes_both_halves() {
+        let key = 0x243f_6a88_85a3_08d3_1319_8a2e_0370_7344_u128;
+        for bit in 0..128 {
+            let a = mix128(0, key);
+            let b = mix128(1 << bit, key);
+            assert_ne!(a as u64, b as u64, "Low half unchanged by bit {}", bit);
+            assert_ne!((a >> 64) as u64, (b >> 64) as u64, "High half unchanged by bit {}", bit);
+            assert_ne!(mix128(7, key), mix128(7, key ^ (1 << bit)));
+        }
+        assert_avalanche("mix128", 128, 128, |bit| {
+            (mix128(12345, key), mix128(12345 ^ (1 << bit), key))
+        });
+    }
+
+    #[test]
+    fn test_hash_u64_avalanche() {
+        let keys = [0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344];
+        assert_avalanche("hash_u64", 64, 64, |bit| {
+            (hash_u64(99, keys) as u128, hash_u64(99 ^ (1 << bit), keys) as u128)
+        });
+    }
+}
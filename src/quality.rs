@@ -0,0 +1,623 @@
+//! Checks of hash quality which can be run against any [Hasher]. (Requires the `quality` feature.)
+//!
+//! These are the checks aHash's own tests hold it to. They can be used to validate a wrapper hasher, an alternative
+//! [BuildHasher], or a custom [Hash] implementation against the same bar.
+//!
+//! Each check returns a [CheckResult] rather than panicking, and the suites ([check_hasher], [check_keyed_hasher]
+//! and [check_build_hasher]) collect them into a [Report]. The checks examine millions of hashes, so they should be
+//! run with optimizations enabled.
+//!
+//! # Example
+//! ```no_run
+//! use ahash::quality::check_build_hasher;
+//! use ahash::RandomState;
+//!
+//! let report = check_build_hasher(&RandomState::with_seeds(1, 2, 3, 4));
+//! assert!(report.passed(), "{}", report);
+//! ```
+pub mod bias;
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use std::collections::HashMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// The maximum number of failure messages kept by a [CheckResult].
+pub const MAX_EXAMPLES: usize = 10;
+
+/// The outcome of a single check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    /// The name of the check (the name of the function that ran it).
+    pub name: &'static str,
+    /// The number of cases (usually comparisons between hashes) examined.
+    pub cases: u64,
+    /// The number of cases which failed.
+    pub failures: u64,
+    /// Descriptions of the first [MAX_EXAMPLES] failures.
+    pub examples: Vec<String>,
+}
+
+impl CheckResult {
+    fn new(name: &'static str) -> Self {
+        CheckResult {
+            name,
+            cases: 0,
+            failures: 0,
+            examples: Vec::new(),
+        }
+    }
+
+    /// Records the outcome of one case.
+    fn check(&mut self, outcome: Result<(), String>) {
+        self.cases += 1;
+        if let Err(message) = outcome {
+            self.failures += 1;
+            if self.examples.len() < MAX_EXAMPLES {
+                self.examples.push(message);
+            }
+        }
+    }
+
+    /// Returns true if no cases failed.
+    pub fn passed(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+impl fmt::Display for CheckResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed() {
+            write!(f, "{}: passed ({} cases)", self.name, self.cases)
+        } else {
+            write!(f, "{}: FAILED {} of {} cases", self.name, self.failures, self.cases)?;
+            for example in &self.examples {
+                write!(f, "\n    {}", example)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The results of a suite of checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub checks: Vec<CheckResult>,
+}
+
+impl Report {
+    /// Returns true if every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(CheckResult::passed)
+    }
+
+    /// The checks which failed.
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|c| !c.passed())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{}", check)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the checks which only need a way to create hashers: [single_bit_flip], [all_bytes_matter],
+/// [no_pair_collisions], [input_affects_every_byte], [finish_is_consistent], [padding_does_not_collide] and [sparse].
+///
+/// Every hasher returned by `new_hasher` must hash the same way (i.e. use the same keys).
+pub fn check_hasher<H: Hasher>(new_hasher: impl Fn() -> H) -> Report {
+    Report {
+        checks: vec![
+            single_bit_flip(&new_hasher),
+            all_bytes_matter(&new_hasher),
+            no_pair_collisions(&new_hasher),
+            input_affects_every_byte(&new_hasher),
+            finish_is_consistent(&new_hasher),
+            padding_does_not_collide(&new_hasher),
+            sparse(&new_hasher),
+        ],
+    }
+}
+
+/// Runs the checks in [check_hasher] using the keys `(0, 0)`, followed by the checks which vary the keys:
+/// [keys_change_output], [single_key_bit_flip], [keys_affect_every_byte] (for an integer, a string and a tuple) and
+/// [length_extension].
+///
+/// `with_keys` creates a hasher from two 128 bit keys.
+pub fn check_keyed_hasher<H: Hasher>(with_keys: impl Fn(u128, u128) -> H) -> Report {
+    let mut report = check_hasher(|| with_keys(0, 0));
+    report.checks.extend([
+        keys_change_output(&with_keys),
+        single_key_bit_flip(&with_keys),
+        keys_affect_every_byte(0_u64, &with_keys),
+        keys_affect_every_byte("", &with_keys),
+        keys_affect_every_byte((0_u32, 0_u32), &with_keys),
+        length_extension(&with_keys),
+    ]);
+    report
+}
+
+/// Runs [check_hasher] on the hashers built by `build_hasher`.
+pub fn check_build_hasher<B: BuildHasher>(build_hasher: &B) -> Report {
+    check_hasher(|| build_hasher.build_hasher())
+}
+
+fn hash_with<T: Hash + ?Sized, H: Hasher>(value: &T, mut hasher: H) -> u64 {
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Counts the bytes and the nibbles which are the same in `a` and `b`.
+pub fn count_same_bytes_and_nibbles(a: u64, b: u64) -> (i32, i32) {
+    let mut same_byte_count = 0;
+    let mut same_nibble_count = 0;
+    for byte in 0..8 {
+        let ba = (a >> (8 * byte)) as u8;
+        let bb = (b >> (8 * byte)) as u8;
+        if ba == bb {
+            same_byte_count += 1;
+        }
+        if ba & 0xF0u8 == bb & 0xF0u8 {
+            same_nibble_count += 1;
+        }
+        if ba & 0x0Fu8 == bb & 0x0Fu8 {
+            same_nibble_count += 1;
+        }
+    }
+    (same_byte_count, same_nibble_count)
+}
+
+/// Checks that two hashes look unrelated: at most `tolerance` bytes and `3 * tolerance` nibbles are the same, and
+/// they differ in between 13 and 51 bits, and between 11 and 53 bits under every rotation of `b`.
+pub fn sufficiently_different(a: u64, b: u64, tolerance: i32) -> Result<(), String> {
+    let (same_byte_count, same_nibble_count) = count_same_bytes_and_nibbles(a, b);
+    if same_byte_count > tolerance {
+        return Err(format!("{:x} vs {:x}: {} bytes are the same", a, b, same_byte_count));
+    }
+    if same_nibble_count > tolerance * 3 {
+        return Err(format!(
+            "{:x} vs {:x}: {} nibbles are the same",
+            a, b, same_nibble_count
+        ));
+    }
+    let flipped_bits = (a ^ b).count_ones();
+    if flipped_bits <= 12 || flipped_bits >= 52 {
+        return Err(format!("{:x} and {:x}: {} bits differ", a, b, flipped_bits));
+    }
+    for rotate in 0..64 {
+        let flipped_bits2 = (a ^ (b.rotate_left(rotate))).count_ones();
+        if flipped_bits2 <= 10 || flipped_bits2 >= 54 {
+            return Err(format!(
+                "{:x} and {:x}: {} bits differ",
+                a,
+                b.rotate_left(rotate),
+                flipped_bits2
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that flipping any single bit of a `u32`, `u64` or `u128` zero changes the hash thoroughly.
+pub fn single_bit_flip<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("single_bit_flip");
+    let compare_value = hash_with(&0u32, new_hasher());
+    for pos in 0..32 {
+        let test_value = hash_with(&(1u32 << pos), new_hasher());
+        result
+            .check(sufficiently_different(compare_value, test_value, 2).map_err(|e| format!("u32 bit {}: {}", pos, e)));
+    }
+    let compare_value = hash_with(&0u64, new_hasher());
+    for pos in 0..64 {
+        let test_value = hash_with(&(1u64 << pos), new_hasher());
+        result
+            .check(sufficiently_different(compare_value, test_value, 2).map_err(|e| format!("u64 bit {}: {}", pos, e)));
+    }
+    let compare_value = hash_with(&0u128, new_hasher());
+    for pos in 0..128 {
+        let test_value = hash_with(&(1u128 << pos), new_hasher());
+        result.check(
+            sufficiently_different(compare_value, test_value, 2).map_err(|e| format!("u128 bit {}: {}", pos, e)),
+        );
+    }
+    result
+}
+
+/// Checks that changing any byte of a 256 byte input changes the hash.
+pub fn all_bytes_matter<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("all_bytes_matter");
+    let mut item = vec![0_u8; 256];
+    let base_hash = hash_with(&item, new_hasher());
+    for pos in 0..256 {
+        item[pos] = 255;
+        let hash = hash_with(&item, new_hasher());
+        result.check(if base_hash != hash {
+            Ok(())
+        } else {
+            Err(format!("Position {} did not affect output", pos))
+        });
+        item[pos] = 0;
+    }
+    result
+}
+
+/// Checks that pairs of `u64`s with a single bit set all hash thoroughly differently from each other and from zeros.
+pub fn no_pair_collisions<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("no_pair_collisions");
+    let base_hash = hash_with(&[0_u64, 0_u64], new_hasher());
+    for bitpos1 in 0..64 {
+        let a = 1_u64 << bitpos1;
+        for bitpos2 in 0..bitpos1 {
+            let b = 1_u64 << bitpos2;
+            let aa = hash_with(&[a, a], new_hasher());
+            let ab = hash_with(&[a, b], new_hasher());
+            let ba = hash_with(&[b, a], new_hasher());
+            let bb = hash_with(&[b, b], new_hasher());
+            let pairs = [
+                (base_hash, aa),
+                (base_hash, ab),
+                (base_hash, ba),
+                (base_hash, bb),
+                (aa, ab),
+                (ab, ba),
+                (ba, bb),
+                (aa, ba),
+                (ab, bb),
+                (aa, bb),
+            ];
+            for (x, y) in pairs {
+                result.check(
+                    sufficiently_different(x, y, 3).map_err(|e| format!("bits {} and {}: {}", bitpos1, bitpos2, e)),
+                );
+            }
+        }
+    }
+    result
+}
+
+/// Checks that hashing up to seven `u32`s drawn from a set of 11 sparse values never produces a collision.
+///
+/// This hashes over 21 million inputs and keeps their hashes in memory, so it is not part of [check_hasher].
+pub fn no_full_collisions<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    fn gen_combinations(options: &[u32; 11], depth: u32, so_far: Vec<u32>, combinations: &mut Vec<Vec<u32>>) {
+        if depth == 0 {
+            return;
+        }
+        for option in options {
+            let mut next = so_far.clone();
+            next.push(*option);
+            combinations.push(next.clone());
+            gen_combinations(options, depth - 1, next, combinations);
+        }
+    }
+
+    let mut result = CheckResult::new("no_full_collisions");
+    let options: [u32; 11] = [
+        0x00000000, 0x10000000, 0x20000000, 0x40000000, 0x80000000, 0xF0000000, 1, 2, 4, 8, 15,
+    ];
+    let mut combinations = Vec::new();
+    gen_combinations(&options, 7, Vec::new(), &mut combinations);
+    let mut map: HashMap<u64, Vec<u8>> = HashMap::new();
+    for combination in combinations {
+        let array: Vec<u8> = combination.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        let mut hasher = new_hasher();
+        hasher.write(&array);
+        let hash = hasher.finish();
+        match map.get(&hash) {
+            Some(value) => result.check(Err(format!(
+                "Found a collision between {:x?} and {:x?}. Hash: {:x?}",
+                value, &array, &hash
+            ))),
+            None => {
+                result.check(Ok(()));
+                map.insert(hash, array);
+            }
+        }
+    }
+    result
+}
+
+/// Checks that changing either key changes the hash thoroughly.
+pub fn keys_change_output<H: Hasher>(with_keys: impl Fn(u128, u128) -> H) -> CheckResult {
+    let mut result = CheckResult::new("keys_change_output");
+    let keys = [(1, 1), (1, 2), (2, 1), (2, 2)];
+    let hashes: Vec<u64> = keys.iter().map(|&(a, b)| hash_with("test", with_keys(a, b))).collect();
+    for i in 0..hashes.len() {
+        for j in i + 1..hashes.len() {
+            result.check(
+                sufficiently_different(hashes[i], hashes[j], 1)
+                    .map_err(|e| format!("keys {:?} vs {:?}: {}", keys[i], keys[j], e)),
+            );
+        }
+    }
+    result
+}
+
+fn each_bit_changes(shift: u64, base: u64, alternatives: &[u64]) -> Result<(), String> {
+    let changed_bits = alternatives
+        .iter()
+        .fold(0_u64, |bits, alternative| bits | (base ^ alternative));
+    if changed_bits == u64::MAX {
+        Ok(())
+    } else {
+        Err(format!(
+            "Bits changed: {:x} on num: {:?}. base {:x}",
+            changed_bits, shift, base
+        ))
+    }
+}
+
+/// Checks that for every byte of a `u128` input and every bit of the output, some value of the byte flips the bit.
+pub fn input_affects_every_byte<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("input_affects_every_byte");
+    let base = hash_with(&0_i32, new_hasher());
+    for shift in 0..16 {
+        let alternatives: Vec<u64> = (0..256_u128)
+            .map(|v| hash_with(&(v << (shift * 8)), new_hasher()))
+            .collect();
+        result.check(each_bit_changes(shift, base, &alternatives));
+    }
+    result
+}
+
+/// Checks that for every byte of each key and every bit of the output, some value of the byte flips the bit when
+/// hashing `item`.
+pub fn keys_affect_every_byte<T: Hash, H: Hasher>(item: T, with_keys: impl Fn(u128, u128) -> H) -> CheckResult {
+    let mut result = CheckResult::new("keys_affect_every_byte");
+    let base = hash_with(&item, with_keys(0, 0));
+    for shift in 0..16 {
+        let mut alternatives1 = vec![];
+        let mut alternatives2 = vec![];
+        for v in 0..256_u128 {
+            let input = v << (shift * 8);
+            alternatives1.push(hash_with(&item, with_keys(input, 0)));
+            alternatives2.push(hash_with(&item, with_keys(0, input)));
+        }
+        result.check(each_bit_changes(shift, base, &alternatives1).map_err(|e| format!("first key: {}", e)));
+        result.check(each_bit_changes(shift, base, &alternatives2).map_err(|e| format!("second key: {}", e)));
+    }
+    result
+}
+
+/// Checks that calling `finish` twice returns the same value.
+pub fn finish_is_consistent<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("finish_is_consistent");
+    let mut hasher = new_hasher();
+    "Foo".hash(&mut hasher);
+    let a = hasher.finish();
+    let b = hasher.finish();
+    result.check(if a == b {
+        Ok(())
+    } else {
+        Err(format!("{:x} then {:x}", a, b))
+    });
+    result
+}
+
+/// Checks that flipping any single bit of either key changes the hash of strings of 4, 8 and 16 bytes thoroughly.
+pub fn single_key_bit_flip<H: Hasher>(with_keys: impl Fn(u128, u128) -> H) -> CheckResult {
+    let mut result = CheckResult::new("single_key_bit_flip");
+    for bit in 0..128 {
+        for input in ["1234", "12345678", "1234567812345678"] {
+            let a = hash_with(input, with_keys(0, 0));
+            let b = hash_with(input, with_keys(0, 1 << bit));
+            let c = hash_with(input, with_keys(1 << bit, 0));
+            for (x, y) in [(a, b), (a, c), (b, c)] {
+                result.check(
+                    sufficiently_different(x, y, 2).map_err(|e| format!("{:?} with key bit {}: {}", input, bit, e)),
+                );
+            }
+        }
+    }
+    result
+}
+
+/// Checks that appending up to 128 copies of a character to short strings changes the hash thoroughly.
+pub fn padding_does_not_collide<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("padding_does_not_collide");
+    let mut compare = |string: &str, value: u64, padded: &str, num: usize, c: u8| {
+        let long = hash_with(padded, new_hasher());
+        let (same_bytes, same_nibbles) = count_same_bytes_and_nibbles(value, long);
+        let flipped_bits = (value ^ long).count_ones();
+        result.check(if same_bytes <= 3 && same_nibbles <= 8 && flipped_bits > 10 {
+            Ok(())
+        } else {
+            Err(format!(
+                "string {:?} + {} bytes of {} -> {:x} vs {:x}",
+                string, num, c, value, long
+            ))
+        });
+    };
+    for c in 0..128u8 {
+        for string in ["", "\0", "\x01", "1234", "12345678", "1234567812345678"] {
+            let value = hash_with(string, new_hasher());
+            let mut padded = string.to_string();
+            for num in 1..=128 {
+                padded.push(c as char);
+                compare(string, value, &padded, num, c);
+            }
+            if !string.is_empty() {
+                let mut padded = string[1..].to_string();
+                padded.push(c as char);
+                for num in 2..=128 {
+                    padded.push(c as char);
+                    compare(string, value, &padded, num, c);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Checks that 8 zero bytes and a one followed by 8 zero bytes hash differently for a range of keys.
+pub fn length_extension<H: Hasher>(with_keys: impl Fn(u128, u128) -> H) -> CheckResult {
+    let mut result = CheckResult::new("length_extension");
+    for key in 0..256 {
+        let v1 = hash_with(&[0_u8, 0, 0, 0, 0, 0, 0, 0], with_keys(key, key));
+        let v2 = hash_with(&[1_u8, 0, 0, 0, 0, 0, 0, 0, 0], with_keys(key, key));
+        result.check(if v1 != v2 {
+            Ok(())
+        } else {
+            Err(format!("key {}: both hash to {:x}", key, v1))
+        });
+    }
+    result
+}
+
+/// Checks that no two 256 byte inputs which are zero except for two bytes collide.
+pub fn sparse<H: Hasher>(new_hasher: impl Fn() -> H) -> CheckResult {
+    let mut result = CheckResult::new("sparse");
+    let mut buf = [0u8; 256];
+    let mut hashes: HashMap<u64, [u8; 4]> = HashMap::new();
+    for idx_1 in 0..255_u8 {
+        for idx_2 in idx_1 + 1..=255_u8 {
+            for value_1 in [1, 2, 4, 8, 16, 32, 64, 128] {
+                for value_2 in [
+                    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 15, 16, 17, 18, 20, 24, 31, 32, 33, 48, 64, 96, 127, 128, 129,
+                    192, 254, 255,
+                ] {
+                    buf[idx_1 as usize] = value_1;
+                    buf[idx_2 as usize] = value_2;
+                    let hash_value = hash_with(&buf, new_hasher());
+                    let key = [idx_1, value_1, idx_2, value_2];
+                    let existing = *hashes.entry(hash_value).or_insert(key);
+                    result.check(if existing == key {
+                        Ok(())
+                    } else {
+                        Err(format!("Collision between {:?} and {:?}", existing, key))
+                    });
+                    buf[idx_1 as usize] = 0;
+                    buf[idx_2 as usize] = 0;
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Checks a [Hash] implementation: hashing a value twice gives the same hash, equal values have equal hashes, and
+/// unequal values have different hashes.
+///
+/// Every pair of values is compared, so this is intended for at most a few thousand values. The values should be
+/// chosen to differ in the ways real keys do, for example in a single field.
+pub fn hash_impl<T: Hash + Eq + fmt::Debug, B: BuildHasher>(
+    values: impl IntoIterator<Item = T>,
+    build_hasher: &B,
+) -> CheckResult {
+    let mut result = CheckResult::new("hash_impl");
+    let values: Vec<T> = values.into_iter().collect();
+    let hashes: Vec<u64> = values
+        .iter()
+        .map(|v| hash_with(v, build_hasher.build_hasher()))
+        .collect();
+    for (value, &hash) in values.iter().zip(&hashes) {
+        let again = hash_with(value, build_hasher.build_hasher());
+        result.check(if again == hash {
+            Ok(())
+        } else {
+            Err(format!("{:?} hashed to {:x} then {:x}", value, hash, again))
+        });
+    }
+    for i in 0..values.len() {
+        for j in i + 1..values.len() {
+            let (a, b) = (&values[i], &values[j]);
+            result.check(match (a == b, hashes[i] == hashes[j]) {
+                (true, false) => Err(format!("{:?} and {:?} are equal but have different hashes", a, b)),
+                (false, true) => Err(format!("{:?} and {:?} collide: {:x}", a, b, hashes[i])),
+                _ => Ok(()),
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RandomState;
+
+    /// A deliberately poor hasher which sums its input.
+    #[derive(Default)]
+    struct SumHasher(u64);
+
+    impl Hasher for SumHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = self.0.wrapping_add(*byte as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_poor_hasher_fails() {
+        let report = Report {
+            checks: vec![
+                single_bit_flip(SumHasher::default),
+                no_pair_collisions(SumHasher::default),
+                finish_is_consistent(SumHasher::default),
+            ],
+        };
+        assert!(!report.passed());
+        let failed: Vec<_> = report.failures().map(|c| c.name).collect();
+        assert_eq!(failed, ["single_bit_flip", "no_pair_collisions"]);
+        assert_eq!(report.checks[0].cases, 32 + 64 + 128);
+        assert_eq!(report.checks[0].examples.len(), MAX_EXAMPLES);
+        assert!(report.to_string().contains("single_bit_flip: FAILED"));
+        assert!(report.to_string().contains("finish_is_consistent: passed (1 cases)"));
+    }
+
+    #[test]
+    fn test_random_state_passes() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        let new_hasher = || state.build_hasher();
+        for check in [
+            single_bit_flip(new_hasher),
+            all_bytes_matter(new_hasher),
+            no_pair_collisions(new_hasher),
+            input_affects_every_byte(new_hasher),
+            finish_is_consistent(new_hasher),
+        ] {
+            assert!(check.passed(), "{}", check);
+        }
+    }
+
+    #[test]
+    fn test_hash_impl() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Good(u32, u32);
+        impl Hash for Good {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+                self.1.hash(state);
+            }
+        }
+        #[derive(Debug, PartialEq, Eq)]
+        struct IgnoresField(u32, u32);
+        impl Hash for IgnoresField {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state);
+            }
+        }
+
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        let result = hash_impl((0..10).flat_map(|a| (0..10).map(move |b| Good(a, b))), &state);
+        assert!(result.passed(), "{}", result);
+        assert_eq!(result.cases, 100 + 100 * 99 / 2);
+        let result = hash_impl(vec![Good(1, 2), Good(1, 2)], &state);
+        assert!(result.passed(), "{}", result);
+
+        let result = hash_impl((0..10).map(|b| IgnoresField(0, b)), &state);
+        assert_eq!(result.failures, 10 * 9 / 2);
+        assert!(result.examples[0].contains("collide"));
+    }
+}
@@ -0,0 +1,548 @@
+//! Measurements of bias in a hash function.
+//!
+//! Unlike the checks in the [parent module](super), which pass or fail, these quantify how far a hasher is from
+//! ideal so that the results can be compared between versions:
+//!
+//! * [avalanche] measures, for each input bit, the probability that flipping it flips each output bit (ideally 0.5).
+//! * [bit_independence] measures the correlation between pairs of output bits flipping together when an input bit
+//!   is flipped (the bit independence criterion; ideally 0).
+//! * [chi_squared] measures how evenly keys are distributed over buckets selected by the low and high bits of the
+//!   hash, as a hash table would.
+//!
+//! [analyze] runs all three on a [KeySet] and returns a [BiasReport], which can be written as JSON with
+//! [BiasReport::to_json].
+//!
+//! # Example
+//! ```
+//! use ahash::quality::bias::{analyze, KeySet};
+//! use ahash::RandomState;
+//! use std::hash::BuildHasher;
+//!
+//! let state = RandomState::with_seeds(1, 2, 3, 4);
+//! let report = analyze(&KeySet::Sequential { start: 0, count: 1000 }, || state.build_hasher());
+//! assert!(report.avalanche.max_bias < 0.15);
+//! println!("{}", report.to_json());
+//! ```
+use core::fmt::Write;
+use core::hash::{Hash, Hasher};
+use std::string::String;
+use std::vec::Vec;
+
+/// A set of keys to analyze a hasher with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySet {
+    /// `count` pseudo-random `u64`s generated from `seed`.
+    Integers { count: usize, seed: u64 },
+    /// `count` consecutive `u64`s starting from `start`.
+    Sequential { start: u64, count: usize },
+    /// Every `u64` with at most `max_bits` bits set.
+    Sparse { max_bits: u32 },
+    /// `count` pseudo-random alphanumeric strings of `len` bytes generated from `seed`. These are hashed as `[u8]`,
+    /// so that they can still be hashed when [avalanche] flips a bit to make them invalid UTF-8.
+    Strings { count: usize, len: usize, seed: u64 },
+}
+
+/// The keys in a [KeySet].
+enum Keys {
+    Integers(Vec<u64>),
+    Bytes(Vec<Vec<u8>>),
+}
+
+/// SplitMix64, used so that key sets are the same on every platform and version.
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn sparse_integers(max_bits: u32, lowest_bit: u32, so_far: u64, out: &mut Vec<u64>) {
+    out.push(so_far);
+    if max_bits > 0 {
+        for bit in lowest_bit..64 {
+            sparse_integers(max_bits - 1, bit + 1, so_far | (1 << bit), out);
+        }
+    }
+}
+
+impl KeySet {
+    /// A short name for the kind of keys.
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeySet::Integers { .. } => "integers",
+            KeySet::Sequential { .. } => "sequential",
+            KeySet::Sparse { .. } => "sparse",
+            KeySet::Strings { .. } => "strings",
+        }
+    }
+
+    fn keys(&self) -> Keys {
+        match *self {
+            KeySet::Integers { count, seed } => {
+                let mut state = seed;
+                Keys::Integers((0..count).map(|_| split_mix(&mut state)).collect())
+            }
+            KeySet::Sequential { start, count } => {
+                Keys::Integers((0..count as u64).map(|i| start.wrapping_add(i)).collect())
+            }
+            KeySet::Sparse { max_bits } => {
+                let mut keys = Vec::new();
+                sparse_integers(max_bits, 0, 0, &mut keys);
+                Keys::Integers(keys)
+            }
+            KeySet::Strings { count, len, seed } => {
+                const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+                let mut state = seed;
+                Keys::Bytes(
+                    (0..count)
+                        .map(|_| {
+                            (0..len)
+                                .map(|_| ALPHANUMERIC[(split_mix(&mut state) % ALPHANUMERIC.len() as u64) as usize])
+                                .collect()
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Writes the parameters of the key set as JSON.
+    fn write_json(&self, out: &mut String) {
+        let _ = match *self {
+            KeySet::Integers { count, seed } => write!(out, r#"{{"count":{},"seed":{}}}"#, count, seed),
+            KeySet::Sequential { start, count } => write!(out, r#"{{"start":{},"count":{}}}"#, start, count),
+            KeySet::Sparse { max_bits } => write!(out, r#"{{"max_bits":{}}}"#, max_bits),
+            KeySet::Strings { count, len, seed } => {
+                write!(out, r#"{{"count":{},"len":{},"seed":{}}}"#, count, len, seed)
+            }
+        };
+    }
+}
+
+fn hash_with<T: Hash + ?Sized, H: Hasher>(value: &T, mut hasher: H) -> u64 {
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Calls `f` with the number of input bits, then for each key and input bit with the hash of the key and the hash
+/// with that bit flipped.
+fn for_each_flip<H: Hasher>(
+    keys: &KeySet,
+    new_hasher: impl Fn() -> H,
+    mut f: impl FnMut(usize, usize, u64, u64),
+) -> (usize, usize) {
+    match keys.keys() {
+        Keys::Integers(keys) => {
+            for key in &keys {
+                let base = hash_with(key, new_hasher());
+                for bit in 0..64 {
+                    f(64, bit, base, hash_with(&(key ^ (1 << bit)), new_hasher()));
+                }
+            }
+            (64, keys.len())
+        }
+        Keys::Bytes(mut keys) => {
+            let input_bits = keys.first().map_or(0, |k| k.len() * 8);
+            for key in &mut keys {
+                let base = hash_with(&key[..], new_hasher());
+                for bit in 0..input_bits {
+                    key[bit / 8] ^= 1 << (bit % 8);
+                    f(input_bits, bit, base, hash_with(&key[..], new_hasher()));
+                    key[bit / 8] ^= 1 << (bit % 8);
+                }
+            }
+            (input_bits, keys.len())
+        }
+    }
+}
+
+/// The result of [avalanche].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Avalanche {
+    /// The number of keys measured.
+    pub samples: usize,
+    /// `matrix[i][j]` is the fraction of keys for which flipping input bit `i` flipped output bit `j`.
+    pub matrix: Vec<[f64; 64]>,
+    /// The largest difference between an entry in the matrix and 0.5.
+    pub max_bias: f64,
+    /// The input and output bits where `max_bias` occurs.
+    pub worst: (usize, usize),
+    /// The root mean square difference between the entries of the matrix and 0.5.
+    pub rms_bias: f64,
+}
+
+/// Measures the probability that flipping each input bit flips each output bit.
+///
+/// For an ideal hash every entry is close to 0.5. With `n` keys, random variation alone gives entries a standard
+/// deviation of about `0.5 / sqrt(n)`.
+pub fn avalanche<H: Hasher>(keys: &KeySet, new_hasher: impl Fn() -> H) -> Avalanche {
+    let mut counts: Vec<[u64; 64]> = Vec::new();
+    let (input_bits, samples) = for_each_flip(keys, new_hasher, |input_bits, bit, base, flipped| {
+        if counts.is_empty() {
+            counts.resize(input_bits, [0; 64]);
+        }
+        let mut diff = base ^ flipped;
+        while diff != 0 {
+            counts[bit][diff.trailing_zeros() as usize] += 1;
+            diff &= diff - 1;
+        }
+    });
+    counts.resize(input_bits, [0; 64]);
+
+    let mut result = Avalanche {
+        samples,
+        matrix: Vec::with_capacity(input_bits),
+        max_bias: 0.0,
+        worst: (0, 0),
+        rms_bias: 0.0,
+    };
+    let mut sum_squares = 0.0;
+    for (i, row) in counts.iter().enumerate() {
+        let mut probabilities = [0.0; 64];
+        for (j, count) in row.iter().enumerate() {
+            let p = *count as f64 / samples.max(1) as f64;
+            let bias = (p - 0.5).abs();
+            if bias > result.max_bias {
+                result.max_bias = bias;
+                result.worst = (i, j);
+            }
+            sum_squares += bias * bias;
+            probabilities[j] = p;
+        }
+        result.matrix.push(probabilities);
+    }
+    result.rms_bias = (sum_squares / (input_bits.max(1) * 64) as f64).sqrt();
+    result
+}
+
+/// The result of [bit_independence].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitIndependence {
+    /// The number of keys measured.
+    pub samples: usize,
+    /// The largest absolute correlation between two output bits flipping, over all input bits.
+    pub max_correlation: f64,
+    /// The input bit and pair of output bits where `max_correlation` occurs.
+    pub worst: (usize, usize, usize),
+    /// The mean absolute correlation.
+    pub mean_correlation: f64,
+}
+
+/// Measures whether output bits flip independently of each other when an input bit is flipped (the bit independence
+/// criterion).
+///
+/// For each input bit and pair of output bits this computes the correlation (phi coefficient) between the two output
+/// bits flipping. For an ideal hash these are close to 0, with a standard deviation of about `1 / sqrt(n)` for `n`
+/// keys.
+pub fn bit_independence<H: Hasher>(keys: &KeySet, new_hasher: impl Fn() -> H) -> BitIndependence {
+    // single[i][j] counts flips of output bit j for input bit i, and pairs[i][j][k] (for j < k) flips of both.
+    let mut single: Vec<[u32; 64]> = Vec::new();
+    let mut pairs: Vec<[[u32; 64]; 64]> = Vec::new();
+    let (input_bits, samples) = for_each_flip(keys, new_hasher, |input_bits, bit, base, flipped| {
+        if single.is_empty() {
+            single.resize(input_bits, [0; 64]);
+            pairs.resize(input_bits, [[0; 64]; 64]);
+        }
+        let diff = base ^ flipped;
+        let mut outer = diff;
+        while outer != 0 {
+            let j = outer.trailing_zeros() as usize;
+            single[bit][j] += 1;
+            outer &= outer - 1;
+            let mut inner = outer;
+            while inner != 0 {
+                pairs[bit][j][inner.trailing_zeros() as usize] += 1;
+                inner &= inner - 1;
+            }
+        }
+    });
+
+    let n = samples as f64;
+    let mut result = BitIndependence {
+        samples,
+        max_correlation: 0.0,
+        worst: (0, 0, 0),
+        mean_correlation: 0.0,
+    };
+    let mut sum = 0.0;
+    let mut count = 0_u64;
+    for i in 0..single.len().min(input_bits) {
+        for j in 0..64 {
+            for k in j + 1..64 {
+                let (a, b, both) = (single[i][j] as f64, single[i][k] as f64, pairs[i][j][k] as f64);
+                let denominator = (a * (n - a) * b * (n - b)).sqrt();
+                // A bit that always or never flips has no defined correlation. (That is reported by `avalanche`.)
+                let correlation = if denominator > 0.0 {
+                    ((n * both - a * b) / denominator).abs()
+                } else {
+                    0.0
+                };
+                if correlation > result.max_correlation {
+                    result.max_correlation = correlation;
+                    result.worst = (i, j, k);
+                }
+                sum += correlation;
+                count += 1;
+            }
+        }
+    }
+    result.mean_correlation = sum / count.max(1) as f64;
+    result
+}
+
+/// Which bits of the hash select the bucket in [chi_squared].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketBits {
+    /// The lowest bits, as used by `std::collections::HashMap` to select a group.
+    Low,
+    /// The highest bits, as used by `HashMap` for its control bytes and by many other tables.
+    High,
+}
+
+/// The result of [chi_squared].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChiSquared {
+    /// Which bits of the hash selected the bucket.
+    pub bits: BucketBits,
+    /// The number of buckets.
+    pub buckets: usize,
+    /// The number of keys.
+    pub samples: usize,
+    /// The chi-squared statistic.
+    pub statistic: f64,
+    /// The degrees of freedom (`buckets - 1`).
+    pub degrees_of_freedom: usize,
+    /// The probability of a statistic at least this large if the hash were uniformly distributed. Very small values
+    /// indicate the keys are unevenly distributed. (Values very close to 1 indicate they are suspiciously even, which
+    /// is expected for sequential keys with some hashes.)
+    pub p_value: f64,
+}
+
+/// Measures how evenly the keys are distributed when the low or high bits of their hashes select a bucket.
+///
+/// The number of buckets is the largest power of two giving an expected count of at least 16 per bucket, up to 2^16.
+pub fn chi_squared<H: Hasher>(keys: &KeySet, new_hasher: impl Fn() -> H, bits: BucketBits) -> ChiSquared {
+    let hashes: Vec<u64> = match keys.keys() {
+        Keys::Integers(keys) => keys.iter().map(|k| hash_with(k, new_hasher())).collect(),
+        Keys::Bytes(keys) => keys.iter().map(|k| hash_with(&k[..], new_hasher())).collect(),
+    };
+    let samples = hashes.len();
+    let bucket_bits = (usize::BITS - (samples / 16).max(2).leading_zeros() - 1).min(16);
+    let buckets = 1_usize << bucket_bits;
+    let mut counts = vec![0_u64; buckets];
+    for hash in &hashes {
+        let bucket = match bits {
+            BucketBits::Low => *hash as usize & (buckets - 1),
+            BucketBits::High => (*hash >> (64 - bucket_bits)) as usize,
+        };
+        counts[bucket] += 1;
+    }
+    let expected = samples as f64 / buckets as f64;
+    let statistic: f64 = counts
+        .iter()
+        .map(|&c| {
+            let d = c as f64 - expected;
+            d * d / expected
+        })
+        .sum();
+    let degrees_of_freedom = buckets - 1;
+    ChiSquared {
+        bits,
+        buckets,
+        samples,
+        statistic,
+        degrees_of_freedom,
+        p_value: chi_squared_upper_tail(statistic, degrees_of_freedom as f64),
+    }
+}
+
+/// The upper tail probability of the chi-squared distribution, using the Wilson-Hilferty approximation.
+fn chi_squared_upper_tail(statistic: f64, degrees_of_freedom: f64) -> f64 {
+    let variance = 2.0 / (9.0 * degrees_of_freedom);
+    let z = ((statistic / degrees_of_freedom).powf(1.0 / 3.0) - (1.0 - variance)) / variance.sqrt();
+    0.5 * erfc(z / core::f64::consts::SQRT_2)
+}
+
+/// The complementary error function (Numerical Recipes' `erfcc`, accurate to about 1.2e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let result = t * poly.exp();
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+/// The results of [analyze].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiasReport {
+    /// The keys analyzed.
+    pub keys: KeySet,
+    pub avalanche: Avalanche,
+    pub bit_independence: BitIndependence,
+    /// The distribution over buckets selected by the low and then the high bits of the hash.
+    pub chi_squared: [ChiSquared; 2],
+}
+
+/// Runs [avalanche], [bit_independence] and [chi_squared] with the given keys.
+pub fn analyze<H: Hasher>(keys: &KeySet, new_hasher: impl Fn() -> H) -> BiasReport {
+    BiasReport {
+        keys: keys.clone(),
+        avalanche: avalanche(keys, &new_hasher),
+        bit_independence: bit_independence(keys, &new_hasher),
+        chi_squared: [
+            chi_squared(keys, &new_hasher, BucketBits::Low),
+            chi_squared(keys, &new_hasher, BucketBits::High),
+        ],
+    }
+}
+
+impl BiasReport {
+    /// Formats the report as a single line of JSON. Probabilities are rounded to 4 decimal places, so that reports
+    /// from the same hasher and keys are identical.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        let _ = write!(out, r#"{{"keys":{{"kind":"{}","params":"#, self.keys.name());
+        self.keys.write_json(&mut out);
+        let a = &self.avalanche;
+        let _ = write!(
+            out,
+            r#"}},"avalanche":{{"samples":{},"max_bias":{:.4},"worst":[{},{}],"rms_bias":{:.4},"matrix":["#,
+            a.samples, a.max_bias, a.worst.0, a.worst.1, a.rms_bias
+        );
+        for (i, row) in a.matrix.iter().enumerate() {
+            out.push_str(if i == 0 { "[" } else { ",[" });
+            for (j, p) in row.iter().enumerate() {
+                let _ = write!(out, "{}{:.4}", if j == 0 { "" } else { "," }, p);
+            }
+            out.push(']');
+        }
+        let b = &self.bit_independence;
+        let _ = write!(
+            out,
+            r#"]}},"bit_independence":{{"samples":{},"max_correlation":{:.4},"worst":[{},{},{}],"mean_correlation":{:.4}}},"chi_squared":["#,
+            b.samples, b.max_correlation, b.worst.0, b.worst.1, b.worst.2, b.mean_correlation
+        );
+        for (i, c) in self.chi_squared.iter().enumerate() {
+            let _ = write!(
+                out,
+                r#"{}{{"bits":"{}","buckets":{},"samples":{},"statistic":{:.4},"degrees_of_freedom":{},"p_value":{:.4}}}"#,
+                if i == 0 { "" } else { "," },
+                match c.bits {
+                    BucketBits::Low => "low",
+                    BucketBits::High => "high",
+                },
+                c.buckets,
+                c.samples,
+                c.statistic,
+                c.degrees_of_freedom,
+                c.p_value
+            );
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Hashes a `u64` with a single multiply, which avalanches poorly into the low bits.
+    #[derive(Default)]
+    struct MultiplyHasher(u64);
+
+    impl Hasher for MultiplyHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.write_u64(*byte as u64);
+            }
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.0 = (self.0 ^ i).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        }
+    }
+
+    #[test]
+    fn test_key_sets() {
+        let count = |keys: KeySet| match keys.keys() {
+            Keys::Integers(keys) => keys.len(),
+            Keys::Bytes(keys) => keys.len(),
+        };
+        assert_eq!(count(KeySet::Sparse { max_bits: 0 }), 1);
+        assert_eq!(count(KeySet::Sparse { max_bits: 2 }), 1 + 64 + 64 * 63 / 2);
+        assert_eq!(count(KeySet::Sequential { start: 5, count: 10 }), 10);
+        match (KeySet::Strings {
+            count: 3,
+            len: 7,
+            seed: 1,
+        })
+        .keys()
+        {
+            Keys::Bytes(keys) => {
+                assert!(keys
+                    .iter()
+                    .all(|k| k.len() == 7 && k.iter().all(u8::is_ascii_alphanumeric)));
+                assert_ne!(keys[0], keys[1]);
+            }
+            Keys::Integers(_) => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_poor_hasher_is_biased() {
+        let keys = KeySet::Integers { count: 500, seed: 1 };
+        let a = avalanche(&keys, MultiplyHasher::default);
+        // Flipping the top input bit only ever flips the top output bit.
+        assert_eq!(a.matrix[63][63], 1.0);
+        assert_eq!(a.matrix[63][0], 0.0);
+        assert_eq!(a.max_bias, 0.5);
+        assert!(bit_independence(&keys, MultiplyHasher::default).max_correlation > 0.5);
+
+        let sequential = KeySet::Sequential {
+            start: 0,
+            count: 1 << 12,
+        };
+        let low = chi_squared(&sequential, || MultiplyHasher(0), BucketBits::Low);
+        assert_eq!(low.buckets, 256);
+        // Consecutive keys spread perfectly evenly over the low bits.
+        assert_eq!(low.statistic, 0.0);
+        assert!(low.p_value > 0.999);
+    }
+
+    #[test]
+    fn test_chi_squared_p_value() {
+        // Median of the chi-squared distribution is about df * (1 - 2 / (9 * df))^3.
+        assert!((chi_squared_upper_tail(99.334, 100.0) - 0.5).abs() < 0.01);
+        // The 99th percentile for 10 degrees of freedom is 23.209.
+        assert!((chi_squared_upper_tail(23.209, 10.0) - 0.01).abs() < 0.001);
+        assert!(chi_squared_upper_tail(1000.0, 100.0) < 1e-10);
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157_299_2).abs() < 1e-6);
+        assert!((erfc(-1.0) - 1.842_700_8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_json() {
+        let json = analyze(&KeySet::Sequential { start: 0, count: 64 }, MultiplyHasher::default).to_json();
+        assert!(json.starts_with(r#"{"keys":{"kind":"sequential","params":{"start":0,"count":64}},"avalanche":"#));
+        assert!(json.contains(r#""chi_squared":[{"bits":"low","buckets":4,"samples":64,"#));
+        assert_eq!(json.matches('[').count(), json.matches(']').count());
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+    }
+}
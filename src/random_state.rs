@@ -23,8 +23,10 @@ use core::sync::atomic;
 #[cfg(feature = "atomic-polyfill")]
 use portable_atomic as atomic;
 
+use crate::build_info::RngSource;
+use crate::operations::LastWrite;
 use alloc::boxed::Box;
-use atomic::{AtomicUsize, Ordering};
+use atomic::{AtomicBool, AtomicUsize, Ordering};
 use core::any::{Any, TypeId};
 use core::fmt;
 use core::hash::BuildHasher;
@@ -46,6 +48,8 @@ pub(crate) const PI2: [u64; 4] = [
 
 cfg_if::cfg_if! {
     if #[cfg(all(feature = "compile-time-rng", any(test, fuzzing)))] {
+        const DEFAULT_RNG_SOURCE: RngSource = RngSource::CompileTime;
+
         #[inline]
         fn get_fixed_seeds() -> &'static [[u64; 4]; 2] {
             use const_random::const_random;
@@ -65,20 +69,64 @@ cfg_if::cfg_if! {
             ];
             &RAND
         }
+
+        #[inline]
+        fn try_get_fixed_seeds() -> Result<&'static [[u64; 4]; 2], RngError> {
+            Ok(get_fixed_seeds())
+        }
+
+        /// The seeds are constants, so there is nothing to replace.
+        #[cfg(feature = "runtime-rng")]
+        fn replace_fixed_seeds() {}
     } else if #[cfg(all(feature = "runtime-rng", not(fuzzing)))] {
+        use atomic::AtomicPtr;
+
+        const DEFAULT_RNG_SOURCE: RngSource = RngSource::Runtime;
+
+        /// The current seeds. Replaced only by [reseed_fixed_seeds], and never freed once published.
+        static SEEDS: AtomicPtr<[[u64; 4]; 2]> = AtomicPtr::new(core::ptr::null_mut());
+
+        fn generate_fixed_seeds() -> Result<*mut [[u64; 4]; 2], RngError> {
+            use crate::convert::Convert;
+
+            let mut result: [u8; 64] = [0; 64];
+            getrandom::fill(&mut result).map_err(|e| RngError::Runtime(e.raw_os_error()))?;
+            Ok(Box::into_raw(Box::new(result.convert())))
+        }
+
         #[inline]
         fn get_fixed_seeds() -> &'static [[u64; 4]; 2] {
-            use crate::convert::Convert;
+            try_get_fixed_seeds().expect("getrandom::fill() failed.")
+        }
 
-            static SEEDS: OnceBox<[[u64; 4]; 2]> = OnceBox::new();
+        #[inline]
+        fn try_get_fixed_seeds() -> Result<&'static [[u64; 4]; 2], RngError> {
+            let mut seeds = SEEDS.load(Ordering::Acquire);
+            if seeds.is_null() {
+                let new = generate_fixed_seeds()?;
+                seeds = match SEEDS.compare_exchange(core::ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => new,
+                    Err(existing) => {
+                        // Safety: `new` was never published so this is the only reference to it.
+                        drop(unsafe { Box::from_raw(new) });
+                        existing
+                    }
+                };
+            }
+            // Safety: Once published seeds are never freed, so the reference is valid for 'static.
+            Ok(unsafe { &*seeds })
+        }
 
-            SEEDS.get_or_init(|| {
-                let mut result: [u8; 64] = [0; 64];
-                getrandom::fill(&mut result).expect("getrandom::fill() failed.");
-                Box::new(result.convert())
-            })
+        fn replace_fixed_seeds() {
+            // The previous seeds are intentionally leaked as references to them may still be held.
+            // If the OS generator fails they are kept, as the default source does for its counter.
+            if let Ok(seeds) = generate_fixed_seeds() {
+                SEEDS.store(seeds, Ordering::Release);
+            }
         }
     } else if #[cfg(feature = "compile-time-rng")] {
+        const DEFAULT_RNG_SOURCE: RngSource = RngSource::CompileTime;
+
         #[inline]
         fn get_fixed_seeds() -> &'static [[u64; 4]; 2] {
             use const_random::const_random;
@@ -98,11 +146,31 @@ cfg_if::cfg_if! {
             ];
             &RAND
         }
+
+        #[inline]
+        fn try_get_fixed_seeds() -> Result<&'static [[u64; 4]; 2], RngError> {
+            Ok(get_fixed_seeds())
+        }
+
+        /// The seeds are constants, so there is nothing to replace.
+        #[cfg(feature = "runtime-rng")]
+        fn replace_fixed_seeds() {}
     } else {
+        const DEFAULT_RNG_SOURCE: RngSource = RngSource::Fixed;
+
         #[inline]
         fn get_fixed_seeds() -> &'static [[u64; 4]; 2] {
             &[PI, PI2]
         }
+
+        #[inline]
+        fn try_get_fixed_seeds() -> Result<&'static [[u64; 4]; 2], RngError> {
+            Ok(get_fixed_seeds())
+        }
+
+        /// The seeds are constants, so there is nothing to replace.
+        #[cfg(feature = "runtime-rng")]
+        fn replace_fixed_seeds() {}
     }
 }
 
@@ -126,6 +194,29 @@ cfg_if::cfg_if! {
 /// (Rust enables ASLR by default)
 pub trait RandomSource {
     fn gen_hasher_seed(&self) -> usize;
+
+    /// Generates the four keys for a new [RandomState].
+    ///
+    /// The default implementation mixes [RandomSource::gen_hasher_seed] with the process wide fixed seeds.
+    /// Sources which can provide more than a `usize` of randomness may override this so that all of the key material
+    /// comes from the source. (For example to make every [RandomState::new] reproducible from a single seeded PRNG.)
+    fn gen_keys(&self) -> [u64; 4] {
+        let fixed = get_fixed_seeds();
+        let state = RandomState::from_keys(&fixed[0], &fixed[1], self.gen_hasher_seed());
+        [state.k0, state.k1, state.k2, state.k3]
+    }
+
+    /// Called by [reseed] to request that the source stop producing the same sequence of seeds it would have
+    /// otherwise. (For example in a child process after `fork`.) The default implementation does nothing.
+    fn reseed(&self) {}
+}
+
+/// Returns `usize` worth of OS provided randomness, if it is available.
+#[cfg(feature = "runtime-rng")]
+fn runtime_random_usize() -> Option<usize> {
+    let mut bytes = [0_u8; core::mem::size_of::<usize>()];
+    getrandom::fill(&mut bytes).ok()?;
+    Some(usize::from_ne_bytes(bytes))
 }
 
 struct DefaultRandomSource {
@@ -164,6 +255,118 @@ impl RandomSource for DefaultRandomSource {
             }
         }
     }
+
+    #[cfg(feature = "runtime-rng")]
+    fn reseed(&self) {
+        if let Some(random) = runtime_random_usize() {
+            self.counter.fetch_add(random, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A [RandomSource] which avoids shared state between threads.
+///
+/// The default source increments a single global atomic counter each time a [RandomState] is created, which can
+/// become a point of contention when many threads create short lived maps. This source instead touches a global
+/// counter only once per thread, and from then on derives seeds from a thread local counter.
+///
+/// It can be installed with [set_random_source]. Alternatively [RandomState::new_thread_local] provides similar
+/// behaviour without changing the global source.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadLocalRandomSource;
+
+#[cfg(feature = "std")]
+impl ThreadLocalRandomSource {
+    /// Creates a new thread local source.
+    pub const fn new() -> ThreadLocalRandomSource {
+        ThreadLocalRandomSource
+    }
+}
+
+/// A [RandomSource] backed by a [rand_core::RngCore].
+///
+/// This allows any random number generator to supply the keys for [RandomState::new]. Because
+/// [RandomSource::gen_keys] is implemented to draw all four keys from the generator, using a seeded PRNG makes the
+/// keys of every [RandomState] created reproducible, which is useful for deterministic simulation tests.
+///
+/// The generator is protected by a mutex so it can be shared between threads.
+/// (Requires the `rand_core` and `std` features.)
+#[cfg(all(feature = "rand_core", feature = "std"))]
+pub struct RngRandomSource<R> {
+    rng: std::sync::Mutex<R>,
+}
+
+#[cfg(all(feature = "rand_core", feature = "std"))]
+impl<R: rand_core::RngCore> RngRandomSource<R> {
+    /// Wraps the provided generator.
+    pub fn new(rng: R) -> RngRandomSource<R> {
+        RngRandomSource {
+            rng: std::sync::Mutex::new(rng),
+        }
+    }
+
+    /// Returns the wrapped generator.
+    pub fn into_inner(self) -> R {
+        self.rng.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn with_rng<T>(&self, f: impl FnOnce(&mut R) -> T) -> T {
+        // A panic while holding the lock cannot leave the generator in an invalid state, so poisoning is ignored.
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut rng)
+    }
+}
+
+#[cfg(all(feature = "rand_core", feature = "std"))]
+impl<R> fmt::Debug for RngRandomSource<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RngRandomSource { .. }")
+    }
+}
+
+#[cfg(all(feature = "rand_core", feature = "std"))]
+impl<R: rand_core::RngCore> RandomSource for RngRandomSource<R> {
+    fn gen_hasher_seed(&self) -> usize {
+        self.with_rng(|rng| rng.next_u64() as usize)
+    }
+
+    fn gen_keys(&self) -> [u64; 4] {
+        self.with_rng(|rng| [rng.next_u64(), rng.next_u64(), rng.next_u64(), rng.next_u64()])
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static THREAD_LOCAL_COUNTER: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+}
+
+#[cfg(feature = "std")]
+impl RandomSource for ThreadLocalRandomSource {
+    fn gen_hasher_seed(&self) -> usize {
+        use crate::operations::folded_multiply;
+
+        static THREADS: AtomicUsize = AtomicUsize::new(0);
+        THREAD_LOCAL_COUNTER.with(|counter| {
+            let mut value = counter.get();
+            if value == 0 {
+                // First use on this thread. Combine a unique thread number with the address of the thread local.
+                let thread = THREADS.fetch_add(1, Ordering::Relaxed) as u64;
+                let address = counter as *const _ as usize as u64;
+                value = folded_multiply(thread ^ PI[0], address ^ PI[1]) | 1;
+            }
+            counter.set(value.wrapping_add(2));
+            (value ^ (value >> 32)) as usize
+        })
+    }
+
+    /// Only the calling thread's counter is reseeded. (After a `fork` this is the only thread.)
+    #[cfg(feature = "runtime-rng")]
+    fn reseed(&self) {
+        if let Some(random) = runtime_random_usize() {
+            THREAD_LOCAL_COUNTER.with(|counter| counter.set(random as u64 | 1));
+        }
+    }
 }
 
 cfg_if::cfg_if! {
@@ -173,6 +376,12 @@ cfg_if::cfg_if! {
                 static RAND_SOURCE: DefaultRandomSource = DefaultRandomSource::default();
                 &RAND_SOURCE
             }
+
+            #[cfg(feature = "std")]
+            #[inline]
+            fn reseed_count() -> usize {
+                0
+            }
         } else {
             /// Provides an optional way to manually supply a source of randomness for Hasher keys.
             ///
@@ -184,16 +393,178 @@ cfg_if::cfg_if! {
             /// method was previously invoked (true) or if the default source is already being used (false).
             #[cfg(not(all(target_arch = "arm", target_os = "none")))]
             pub fn set_random_source(source: impl RandomSource + Send + Sync + 'static) -> Result<(), bool> {
-                RAND_SOURCE.set(Box::new(Box::new(source))).map_err(|s| s.as_ref().type_id() != TypeId::of::<&DefaultRandomSource>())
+                RAND_SOURCE.set(Box::new(Box::new(source))).map_err(|s| s.as_ref().type_id() != TypeId::of::<&DefaultRandomSource>())?;
+                CUSTOM_RNG_SOURCE.store(true, Ordering::Release);
+                Ok(())
             }
 
             #[inline]
             fn get_src() -> &'static dyn RandomSource {
+                #[cfg(all(feature = "atfork", unix))]
+                reseed_if_forked();
                 RAND_SOURCE.get_or_init(|| Box::new(Box::new(DefaultRandomSource::new()))).as_ref()
             }
+
+            /// The number of times [reseed] has been called.
+            #[cfg(feature = "runtime-rng")]
+            static RESEEDS: AtomicUsize = AtomicUsize::new(0);
+
+            /// Returns the number of times [reseed] has been called, so that keys cached from before a reseed can be
+            /// replaced.
+            #[cfg(feature = "std")]
+            #[inline]
+            fn reseed_count() -> usize {
+                #[cfg(all(feature = "atfork", unix))]
+                reseed_if_forked();
+                #[cfg(feature = "runtime-rng")]
+                return RESEEDS.load(Ordering::Acquire);
+                #[cfg(not(feature = "runtime-rng"))]
+                return 0;
+            }
+
+            /// Refreshes the randomness aHash uses to create new [RandomState]s.
+            ///
+            /// This is intended for processes which `fork` after aHash has been used. Without reseeding every child
+            /// inherits the parent's source of randomness, and so creates the same sequence of [RandomState]s.
+            /// Calling this in the child (or installing `reseed_on_fork` with the `atfork` feature) gives each child
+            /// independent keys.
+            ///
+            /// This calls [RandomSource::reseed] on the active source, which affects [RandomState::new],
+            /// [RandomState::new_thread_local] and [RandomState::generate_with]. The seeds behind
+            /// [RandomState::with_seed] and [AHasher::default](crate::AHasher) are not changed, so existing
+            /// [RandomState]s, hashers and maps are unaffected. To replace those seeds as well, use
+            /// [reseed_fixed_seeds].
+            ///
+            /// This is only available with the `runtime-rng` feature. With `compile-time-rng` alone the seeds are
+            /// constants built into the binary and there is no runtime source to refresh them from, so a forked child
+            /// cannot be given independent keys.
+            #[cfg(feature = "runtime-rng")]
+            pub fn reseed() {
+                get_src().reseed();
+                RESEEDS.fetch_add(1, Ordering::AcqRel);
+            }
+
+            /// Like [reseed], but also obtains new process wide seeds from the OS, so that [RandomState::with_seed] and
+            /// [AHasher::default](crate::AHasher) produce different hashes afterwards.
+            ///
+            /// This is opt-in because it breaks anything which relies on those hashes staying the same. A map using
+            /// `BuildHasherDefault<AHasher>`, or one whose [RandomState] is recreated with [RandomState::with_seed],
+            /// will no longer find the entries it held before the call. Only use it in a child process which does
+            /// not use such maps (or hashes) inherited from its parent. Existing [RandomState]s keep their keys.
+            ///
+            /// This is not done by `reseed_on_fork`. Like [reseed], it is only available with the `runtime-rng`
+            /// feature, and has no effect on the seeds if they were chosen at compile time.
+            #[cfg(feature = "runtime-rng")]
+            pub fn reseed_fixed_seeds() {
+                replace_fixed_seeds();
+                reseed();
+            }
+
+            /// Set by the `fork` handler installed by [reseed_on_fork].
+            #[cfg(all(feature = "atfork", unix))]
+            static RESEED_PENDING: AtomicBool = AtomicBool::new(false);
+
+            #[cfg(all(feature = "atfork", unix))]
+            #[inline]
+            fn reseed_if_forked() {
+                if RESEED_PENDING.load(Ordering::Relaxed) && RESEED_PENDING.swap(false, Ordering::AcqRel) {
+                    reseed();
+                }
+            }
+
+            /// The error returned by [reseed_on_fork] if the handler cannot be registered.
+            #[cfg(all(feature = "atfork", unix))]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct AtForkError {
+                code: i32,
+            }
+
+            #[cfg(all(feature = "atfork", unix))]
+            impl AtForkError {
+                /// The error code returned by `pthread_atfork`.
+                pub fn code(&self) -> i32 {
+                    self.code
+                }
+            }
+
+            #[cfg(all(feature = "atfork", unix))]
+            impl fmt::Display for AtForkError {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "pthread_atfork failed with error {}", self.code)
+                }
+            }
+
+            #[cfg(all(feature = "atfork", unix, feature = "std"))]
+            impl std::error::Error for AtForkError {}
+
+            /// Installs a `pthread_atfork` handler which arranges for [reseed] to be called in the child process after
+            /// every `fork`. Calling this more than once has no further effect.
+            ///
+            /// Only async-signal-safe operations may be performed between `fork` and `exec`, so the handler just sets a
+            /// flag. The reseed is performed by the first call that needs the source of randomness, such as
+            /// [RandomState::new], in the child.
+            #[cfg(all(feature = "atfork", unix))]
+            pub fn reseed_on_fork() -> Result<(), AtForkError> {
+                extern "C" fn child() {
+                    RESEED_PENDING.store(true, Ordering::Release);
+                }
+
+                static INSTALLED: AtomicBool = AtomicBool::new(false);
+                if INSTALLED.swap(true, Ordering::AcqRel) {
+                    return Ok(());
+                }
+                match unsafe { libc::pthread_atfork(None, None, Some(child)) } {
+                    0 => Ok(()),
+                    code => {
+                        INSTALLED.store(false, Ordering::Release);
+                        Err(AtForkError { code })
+                    }
+                }
+            }
         }
 }
 
+/// The error returned by [RandomState::try_new] when the keys of a new [RandomState] would not be secret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RngError {
+    /// Neither the `runtime-rng` nor the `compile-time-rng` feature is enabled, and no source has been installed
+    /// with `set_random_source`. Keys would be derived from fixed, publicly known seeds.
+    WeakSource,
+    /// The OS random number generator failed. Contains the OS error code, if there is one.
+    Runtime(Option<i32>),
+}
+
+impl fmt::Display for RngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RngError::WeakSource => f.write_str("no source of randomness is available, only fixed seeds"),
+            RngError::Runtime(Some(code)) => write!(f, "the OS random number generator failed with error {}", code),
+            RngError::Runtime(None) => f.write_str("the OS random number generator failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RngError {}
+
+/// Set once a source has been installed with `set_random_source`.
+static CUSTOM_RNG_SOURCE: AtomicBool = AtomicBool::new(false);
+
+/// Where new [RandomState]s currently get their keys. Reported by [build_info](crate::build_info).
+pub(crate) fn rng_source() -> RngSource {
+    if CUSTOM_RNG_SOURCE.load(Ordering::Acquire) {
+        RngSource::Custom
+    } else {
+        DEFAULT_RNG_SOURCE
+    }
+}
+
+/// Whether the process wide seeds are the hardcoded constants.
+pub(crate) fn fixed_seeds_are_pi() -> bool {
+    *get_fixed_seeds() == [PI, PI2]
+}
+
 /// Provides a [Hasher] factory. This is typically used (e.g. by [HashMap]) to create
 /// [AHasher]s in order to hash the keys of the map. See `build_hasher` below.
 ///
@@ -210,6 +581,8 @@ cfg_if::cfg_if! {
 /// |`generate_with`| Each instance unique|`u64` x 4 + [RandomSource]|
 /// |`with_seed`    | Fixed per process   |`u64` + static random number|
 /// |`with_seeds`   | Fixed               |`u64` x 4|
+/// |`from_bytes`   | Fixed               |`&[u8]` of any length|
+/// |`derive`       | Fixed per parent    |Parent `RandomState` + label|
 ///
 #[derive(Clone)]
 pub struct RandomState {
@@ -232,9 +605,76 @@ impl RandomState {
     ///
     #[inline]
     pub fn new() -> RandomState {
-        let src = get_src();
-        let fixed = get_fixed_seeds();
-        Self::from_keys(&fixed[0], &fixed[1], src.gen_hasher_seed())
+        #[cfg(feature = "test-determinism")]
+        if let Some(state) = crate::testing::next_deterministic_state() {
+            return state;
+        }
+        let [k0, k1, k2, k3] = get_src().gen_keys();
+        RandomState { k0, k1, k2, k3 }
+    }
+
+    /// Like [RandomState::new], but returns an error instead of a `RandomState` whose keys could be predicted.
+    ///
+    /// This fails with [RngError::WeakSource] if the only source of randomness is the fixed seeds used when neither
+    /// `runtime-rng` nor `compile-time-rng` is enabled (unless a source has been installed with [set_random_source]).
+    /// It fails with [RngError::Runtime] if the OS random number generator fails, where [RandomState::new] would
+    /// panic. (To rule out the first case at compile time, enable the `require-strong-rng` feature.)
+    pub fn try_new() -> Result<RandomState, RngError> {
+        if rng_source() == RngSource::Fixed {
+            return Err(RngError::WeakSource);
+        }
+        try_get_fixed_seeds()?;
+        Ok(RandomState::new())
+    }
+
+    /// Create a new `RandomState` `BuildHasher` with all four keys drawn from the provided random number generator.
+    ///
+    /// Using a cryptographically secure generator gives the same strength as [RandomState::new]. Using a seeded PRNG
+    /// gives reproducible keys. (Requires the `rand_core` feature.)
+    #[cfg(feature = "rand_core")]
+    pub fn from_rng<R: rand_core::RngCore + ?Sized>(rng: &mut R) -> RandomState {
+        RandomState {
+            k0: rng.next_u64(),
+            k1: rng.next_u64(),
+            k2: rng.next_u64(),
+            k3: rng.next_u64(),
+        }
+    }
+
+    /// Create a new `RandomState` `BuildHasher` using random keys, without touching any state shared between threads.
+    ///
+    /// The first call on each thread creates a per-thread `RandomState` via [RandomState::new]. Subsequent calls on
+    /// that thread derive unique keys from it and a thread local counter. This avoids contention on the global
+    /// counter used by the default [RandomSource] when creating many short lived maps in parallel.
+    ///
+    /// The resulting keys are of the same quality as those from [RandomState::new].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new_thread_local() -> RandomState {
+        use core::cell::Cell;
+
+        #[cfg(feature = "test-determinism")]
+        if let Some(state) = crate::testing::next_deterministic_state() {
+            return state;
+        }
+
+        thread_local! {
+            // The thread's keys, the number of states derived from them, and the reseed they were created after.
+            static THREAD_KEYS: (Cell<[u64; 4]>, Cell<usize>, Cell<usize>) =
+                const { (Cell::new([0; 4]), Cell::new(0), Cell::new(usize::MAX)) };
+        }
+        THREAD_KEYS.with(|(keys, counter, reseeds)| {
+            let current = reseed_count();
+            if reseeds.get() != current {
+                let state = RandomState::new();
+                keys.set([state.k0, state.k1, state.k2, state.k3]);
+                counter.set(0);
+                reseeds.set(current);
+            }
+            let count = counter.get();
+            counter.set(count.wrapping_add(1));
+            Self::from_keys(&keys.get(), &get_fixed_seeds()[1], count)
+        })
     }
 
     /// Create a new `RandomState` `BuildHasher` based on the provided seeds, but in such a way
@@ -257,6 +697,11 @@ impl RandomState {
         let &[k0, k1, k2, k3] = a;
         let mut hasher = AHasher::from_random_state(&RandomState { k0, k1, k2, k3 });
         hasher.write_usize(c);
+        Self::mix_keys(hasher, b)
+    }
+
+    /// Produces a new set of keys by hashing each of `b` with a copy of `hasher`.
+    fn mix_keys(hasher: AHasher, b: &[u64; 4]) -> RandomState {
         let mix = |l: u64, r: u64| {
             let mut h = hasher.clone();
             h.write_u64(l);
@@ -271,6 +716,47 @@ impl RandomState {
         }
     }
 
+    /// Derives a child `RandomState` from this one and a label.
+    ///
+    /// This allows many independent but reproducible hashers (for example one per table, per shard, or per row of a
+    /// sketch) to be created from a single configured seed. The same parent and label always produce the same child,
+    /// while different labels produce children whose keys are unrelated to each other and to the parent.
+    /// Any type implementing [Hash] can be used as a label, such as a `&str` name or a `u64` index.
+    #[cfg_attr(
+        feature = "std",
+        doc = r##" # Examples
+```
+    use ahash::RandomState;
+
+    let master = RandomState::with_seeds(1, 2, 3, 4);
+    let shards: Vec<RandomState> = (0..16_u64).map(|i| master.derive(&i)).collect();
+    let users = master.derive("users");
+    assert_eq!(users.hash_one("Bob"), master.derive("users").hash_one("Bob"));
+    assert_ne!(shards[0].hash_one("Bob"), shards[1].hash_one("Bob"));
+```
+    "##
+    )]
+    pub fn derive<L: Hash + ?Sized>(&self, label: &L) -> RandomState {
+        let mut hasher = AHasher::from_random_state(self);
+        // A 128 bit write ensures every key of the parent affects the result regardless of the label's type.
+        hasher.write_u128(((PI[1] as u128) << 64) | PI[0] as u128);
+        label.hash(&mut hasher);
+        Self::mix_keys(hasher, &PI2)
+    }
+
+    /// Builds a `RandomState` from an arbitrary length seed, such as a passphrase or a configuration string.
+    /// All `RandomState`s created from the same bytes will produce identical hashers.
+    ///
+    /// The seed is condensed into four keys by hashing it with fixed keys, so it does not need to be of any
+    /// particular length or format. As with [RandomState::with_seeds], the seed must be unpredictable if DOS
+    /// resistance is desired.
+    pub fn from_bytes(seed: &[u8]) -> RandomState {
+        let [k0, k1, k2, k3] = PI;
+        let mut hasher = AHasher::from_random_state(&RandomState { k0, k1, k2, k3 });
+        hasher.write(seed);
+        Self::mix_keys(hasher, &PI2)
+    }
+
     /// Internal. Used by Default.
     #[inline]
     pub(crate) fn with_fixed_keys() -> RandomState {
@@ -278,6 +764,19 @@ impl RandomState {
         RandomState { k0, k1, k2, k3 }
     }
 
+    /// Internal. Derives keys from the current state of `hasher`, so that hashes made with them are as hard to
+    /// predict as `hasher`'s are. Used to hash collections whose iteration order is not fixed.
+    #[cfg(feature = "std")]
+    pub(crate) fn from_hasher<H: Hasher>(hasher: &mut H) -> RandomState {
+        let mut keys = [0; 4];
+        for key in keys.iter_mut() {
+            *key = hasher.finish();
+            hasher.write_u64(*key);
+        }
+        let [k0, k1, k2, k3] = keys;
+        RandomState::with_seeds(k0, k1, k2, k3)
+    }
+
     /// Build a `RandomState` from a single key. The provided key does not need to be of high quality,
     /// but all `RandomState`s created from the same key will produce identical hashers.
     /// (In contrast to `generate_with` above)
@@ -356,6 +855,47 @@ impl RandomState {
         use crate::specialize::CallHasher;
         T::get_hash(&x, self)
     }
+
+    /// Calculates a hash of a collection of values which does not depend on the order they are provided in.
+    /// This is useful for hashing unordered collections such as `HashSet` whose iteration order is arbitrary.
+    ///
+    /// Each item is hashed independently with a hasher from this `RandomState`. The results are combined with
+    /// addition, which is commutative. Because the per-item hashes are keyed, an attacker cannot choose items whose
+    /// hashes cancel out. The combined value (together with the number of items) is then hashed again so that the
+    /// output is well distributed.
+    ///
+    /// Note that items are treated as a multiset: a value appearing twice affects the result differently than one
+    /// appearing once.
+    #[cfg_attr(
+        feature = "std",
+        doc = r##" # Examples
+```
+    use ahash::RandomState;
+
+    let hash_builder = RandomState::with_seeds(1, 2, 3, 4);
+    let a = hash_builder.hash_unordered(&["a", "b", "c"]);
+    let b = hash_builder.hash_unordered(&["c", "a", "b"]);
+    assert_eq!(a, b);
+```
+    "##
+    )]
+    pub fn hash_unordered<T: Hash, I: IntoIterator<Item = T>>(&self, items: I) -> u64 {
+        let mut count: u64 = 0;
+        let mut sum: u64 = 0;
+        let mut mixed: u64 = 0;
+        for item in items {
+            let mut hasher = self.build_hasher();
+            item.hash(&mut hasher);
+            let hash = hasher.finish();
+            count = count.wrapping_add(1);
+            sum = sum.wrapping_add(hash);
+            mixed = mixed.wrapping_add(crate::operations::folded_multiply(hash, hash ^ self.k2));
+        }
+        let mut hasher = self.build_hasher();
+        hasher.write_u64(count);
+        hasher.write_u128(((mixed as u128) << 64) | sum as u128);
+        hasher.finish()
+    }
 }
 
 /// Creates an instance of RandomState using keys obtained from the random number generator.
@@ -460,28 +1000,46 @@ impl BuildHasher for RandomState {
     }
 }
 
-#[cfg(specialize)]
 impl RandomState {
     #[inline]
-    pub(crate) fn hash_as_u64<T: Hash + ?Sized>(&self, value: &T) -> u64 {
-        let mut hasher = AHasherU64 {
+    pub(crate) fn build_u64_hasher(&self) -> AHasherU64 {
+        AHasherU64 {
             buffer: self.k1,
             pad: self.k0,
-        };
+            extra_keys: [self.k2, self.k3],
+        }
+    }
+
+    #[inline]
+    pub(crate) fn build_fixed_length_hasher(&self) -> AHasherFixed {
+        AHasherFixed(self.build_hasher())
+    }
+
+    #[inline]
+    pub(crate) fn build_str_hasher(&self) -> AHasherStr {
+        AHasherStr(self.build_hasher(), LastWrite::Other)
+    }
+
+    #[cfg(specialize)]
+    #[inline]
+    pub(crate) fn hash_as_u64<T: Hash + ?Sized>(&self, value: &T) -> u64 {
+        let mut hasher = self.build_u64_hasher();
         value.hash(&mut hasher);
         hasher.finish()
     }
 
+    #[cfg(specialize)]
     #[inline]
     pub(crate) fn hash_as_fixed_length<T: Hash + ?Sized>(&self, value: &T) -> u64 {
-        let mut hasher = AHasherFixed(self.build_hasher());
+        let mut hasher = self.build_fixed_length_hasher();
         value.hash(&mut hasher);
         hasher.finish()
     }
 
+    #[cfg(specialize)]
     #[inline]
     pub(crate) fn hash_as_str<T: Hash + ?Sized>(&self, value: &T) -> u64 {
-        let mut hasher = AHasherStr(self.build_hasher());
+        let mut hasher = self.build_str_hasher();
         value.hash(&mut hasher);
         hasher.finish()
     }
@@ -516,6 +1074,129 @@ mod test {
         assert_eq!(PI, get_fixed_seeds()[0]);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_thread_local_unique() {
+        let a = RandomState::new_thread_local();
+        let b = RandomState::new_thread_local();
+        let c = std::thread::spawn(RandomState::new_thread_local).join().unwrap();
+        let hashes = [a.hash_one(1_u64), b.hash_one(1_u64), c.hash_one(1_u64)];
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        assert_ne!(hashes[1], hashes[2]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_thread_local_source_unique() {
+        let src = ThreadLocalRandomSource::new();
+        let a = src.gen_hasher_seed();
+        let b = src.gen_hasher_seed();
+        let c = std::thread::spawn(move || src.gen_hasher_seed()).join().unwrap();
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(b, c);
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn test_from_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let a = RandomState::from_rng(&mut StdRng::seed_from_u64(1));
+        let b = RandomState::from_rng(&mut StdRng::seed_from_u64(1));
+        let c = RandomState::from_rng(&mut StdRng::seed_from_u64(2));
+        assert_eq!(a.hash_one("test"), b.hash_one("test"));
+        assert_ne!(a.hash_one("test"), c.hash_one("test"));
+    }
+
+    #[cfg(all(feature = "rand_core", feature = "std"))]
+    #[test]
+    fn test_rng_random_source() {
+        use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+        let source = RngRandomSource::new(StdRng::seed_from_u64(1));
+        let mut rng = StdRng::seed_from_u64(1);
+        let [k0, k1, k2, k3] = source.gen_keys();
+        assert_eq!(
+            RandomState { k0, k1, k2, k3 }.hash_one(1),
+            RandomState::from_rng(&mut rng).hash_one(1)
+        );
+        assert_ne!(source.gen_keys(), source.gen_keys());
+        let mut expected = StdRng::seed_from_u64(1);
+        for _ in 0..12 {
+            expected.next_u64();
+        }
+        assert_eq!(source.into_inner().next_u64(), expected.next_u64());
+    }
+
+    #[test]
+    fn test_derive() {
+        let parent = RandomState::with_seeds(1, 2, 3, 4);
+        let a = parent.derive("a");
+        assert_eq!(a.hash_one(1_u64), parent.derive("a").hash_one(1_u64));
+        assert_ne!(a.hash_one(1_u64), parent.derive("b").hash_one(1_u64));
+        assert_ne!(a.hash_one(1_u64), parent.hash_one(1_u64));
+        assert_ne!(
+            parent.derive(&0_u64).hash_one(1_u64),
+            parent.derive(&1_u64).hash_one(1_u64)
+        );
+        for other in [
+            RandomState::with_seeds(0, 2, 3, 4),
+            RandomState::with_seeds(1, 0, 3, 4),
+            RandomState::with_seeds(1, 2, 0, 4),
+            RandomState::with_seeds(1, 2, 3, 0),
+        ] {
+            assert_ne!(a.hash_one(1_u64), other.derive("a").hash_one(1_u64));
+        }
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let a = RandomState::from_bytes(b"correct horse battery staple");
+        let b = RandomState::from_bytes(b"correct horse battery staple");
+        assert_eq!(a.hash_one("test"), b.hash_one("test"));
+        assert_ne!(
+            a.hash_one("test"),
+            RandomState::from_bytes(b"correct horse battery stapl").hash_one("test")
+        );
+        assert_ne!(
+            RandomState::from_bytes(b"").hash_one("test"),
+            RandomState::from_bytes(&[0]).hash_one("test")
+        );
+    }
+
+    #[test]
+    fn test_hash_unordered() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        assert_eq!(state.hash_unordered([1_u64, 2, 3]), state.hash_unordered([3_u64, 1, 2]));
+        assert_ne!(state.hash_unordered([1_u64, 2, 3]), state.hash_unordered([1_u64, 2, 4]));
+        assert_ne!(state.hash_unordered([1_u64, 2]), state.hash_unordered([1_u64, 2, 2]));
+        assert_ne!(state.hash_unordered([1_u64, 1]), state.hash_unordered([2_u64, 0]));
+        let empty: [u64; 0] = [];
+        assert_ne!(state.hash_unordered(empty), state.hash_unordered([0_u64]));
+        let other = RandomState::with_seeds(1, 2, 3, 5);
+        assert_ne!(state.hash_unordered([1_u64, 2, 3]), other.hash_unordered([1_u64, 2, 3]));
+    }
+
+    #[test]
+    fn test_try_new() {
+        #[cfg(any(feature = "runtime-rng", feature = "compile-time-rng"))]
+        {
+            let a = RandomState::try_new().unwrap();
+            let b = RandomState::try_new().unwrap();
+            assert_ne!(a.hash_one(1_u64), b.hash_one(1_u64));
+        }
+        #[cfg(not(any(feature = "runtime-rng", feature = "compile-time-rng")))]
+        if rng_source() == RngSource::Fixed {
+            assert_eq!(RandomState::try_new().unwrap_err(), RngError::WeakSource);
+        }
+        assert_eq!(
+            RngError::Runtime(Some(5)).to_string(),
+            "the OS random number generator failed with error 5"
+        );
+    }
+
     #[test]
     fn test_with_seeds_const() {
         const _CONST_RANDOM_STATE: RandomState = RandomState::with_seeds(17, 19, 21, 23);
@@ -0,0 +1,143 @@
+//! A portable implementation of the AES rounds used by the AES hasher.
+//!
+//! These perform the same steps as the x86 `aesenc` and `aesdec` instructions (which [crate::operations] also emulates
+//! on ARM). They are far too slow to hash with, but serve as a reference the intrinsics can be checked against.
+
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+const fn s_box() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut x = 0;
+    while x < 256 {
+        // The multiplicative inverse is x^254 (which maps 0 to 0, as AES requires).
+        let (mut inverse, mut base, mut exponent) = (1, x as u8, 254);
+        while exponent != 0 {
+            if exponent & 1 != 0 {
+                inverse = gf_mul(inverse, base);
+            }
+            base = gf_mul(base, base);
+            exponent >>= 1;
+        }
+        table[x] = inverse
+            ^ inverse.rotate_left(1)
+            ^ inverse.rotate_left(2)
+            ^ inverse.rotate_left(3)
+            ^ inverse.rotate_left(4)
+            ^ 0x63;
+        x += 1;
+    }
+    table
+}
+
+const fn inverse_s_box() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut x = 0;
+    while x < 256 {
+        table[S_BOX[x] as usize] = x as u8;
+        x += 1;
+    }
+    table
+}
+
+const S_BOX: [u8; 256] = s_box();
+const INVERSE_S_BOX: [u8; 256] = inverse_s_box();
+
+/// Applies `mix` to each column of the state. Byte `i` of the value is row `i % 4` of column `i / 4`.
+fn mix_columns(state: [u8; 16], mix: [u8; 4]) -> [u8; 16] {
+    let mut result = [0; 16];
+    for column in 0..4 {
+        for row in 0..4 {
+            result[column * 4 + row] =
+                (0..4).fold(0, |acc, i| acc ^ gf_mul(state[column * 4 + i], mix[(i + 4 - row) % 4]));
+        }
+    }
+    result
+}
+
+/// One round of AES encryption: ShiftRows, SubBytes and MixColumns, followed by xoring in `key`.
+pub(crate) fn aesenc(value: u128, key: u128) -> u128 {
+    let state = value.to_le_bytes();
+    let mut shifted = [0; 16];
+    for (i, byte) in shifted.iter_mut().enumerate() {
+        let (column, row) = (i / 4, i % 4);
+        *byte = S_BOX[state[(column + row) % 4 * 4 + row] as usize];
+    }
+    u128::from_le_bytes(mix_columns(shifted, [2, 3, 1, 1])) ^ key
+}
+
+/// One round of AES decryption: InvShiftRows, InvSubBytes and InvMixColumns, followed by xoring in `key`.
+pub(crate) fn aesdec(value: u128, key: u128) -> u128 {
+    let state = value.to_le_bytes();
+    let mut shifted = [0; 16];
+    for (i, byte) in shifted.iter_mut().enumerate() {
+        let (column, row) = (i / 4, i % 4);
+        *byte = INVERSE_S_BOX[state[(column + 4 - row) % 4 * 4 + row] as usize];
+    }
+    u128::from_le_bytes(mix_columns(shifted, [14, 11, 13, 9])) ^ key
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_s_box() {
+        assert_eq!(S_BOX[0x00], 0x63);
+        assert_eq!(S_BOX[0x01], 0x7c);
+        assert_eq!(S_BOX[0x53], 0xed);
+        assert_eq!(S_BOX[0xff], 0x16);
+        assert_eq!(INVERSE_S_BOX[0x63], 0x00);
+    }
+
+    #[test]
+    fn test_known_answers() {
+        // From Intel's AES-NI white paper.
+        let state = 0x7b5b_5465_7374_5665_6374_6f72_5d53_475d;
+        let key = 0x4869_2853_6861_7929_5b47_7565_726f_6e5d;
+        assert_eq!(aesenc(state, key), 0xa831_1c2f_9fdb_a3c5_8b10_4b58_ded7_e595);
+        assert_eq!(aesdec(state, key), 0x138a_c342_faea_2787_b58e_b95e_b730_392a);
+    }
+
+    #[cfg(any(
+        all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "aes", not(miri)),
+        all(target_arch = "aarch64", target_feature = "aes", not(miri)),
+        all(feature = "nightly-arm-aes", target_arch = "arm", target_feature = "aes", not(miri)),
+    ))]
+    #[test]
+    fn test_matches_hardware() {
+        use crate::operations;
+        let mut value = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210_u128;
+        for i in 0..10_000_u128 {
+            let key = value.rotate_left(37) ^ i;
+            assert_eq!(
+                aesenc(value, key),
+                operations::aesenc(value, key),
+                "aesenc({:x}, {:x})",
+                value,
+                key
+            );
+            assert_eq!(
+                aesdec(value, key),
+                operations::aesdec(value, key),
+                "aesdec({:x}, {:x})",
+                value,
+                key
+            );
+            value = operations::aesenc(value, key);
+        }
+    }
+}
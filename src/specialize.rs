@@ -8,10 +8,20 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std as alloc;
 
+#[cfg(specialize)]
+use alloc::borrow::Cow;
+#[cfg(specialize)]
+use alloc::boxed::Box;
+#[cfg(specialize)]
+use alloc::rc::Rc;
 #[cfg(specialize)]
 use alloc::string::String;
 #[cfg(specialize)]
+use alloc::sync::Arc;
+#[cfg(specialize)]
 use alloc::vec::Vec;
+#[cfg(specialize)]
+use core::num::*;
 
 /// Provides a way to get an optimized hasher for a given data type.
 /// Rather than using a Hasher generically which can hash any value, this provides a way to get a specialized hash
@@ -73,6 +83,28 @@ call_hasher_impl_u64!(&i8);
 call_hasher_impl_u64!(&i16);
 call_hasher_impl_u64!(&i32);
 call_hasher_impl_u64!(&i64);
+call_hasher_impl_u64!(char);
+call_hasher_impl_u64!(bool);
+call_hasher_impl_u64!(NonZeroU8);
+call_hasher_impl_u64!(NonZeroU16);
+call_hasher_impl_u64!(NonZeroU32);
+call_hasher_impl_u64!(NonZeroU64);
+call_hasher_impl_u64!(NonZeroI8);
+call_hasher_impl_u64!(NonZeroI16);
+call_hasher_impl_u64!(NonZeroI32);
+call_hasher_impl_u64!(NonZeroI64);
+call_hasher_impl_u64!((u32, u32));
+call_hasher_impl_u64!(&char);
+call_hasher_impl_u64!(&bool);
+call_hasher_impl_u64!(&NonZeroU8);
+call_hasher_impl_u64!(&NonZeroU16);
+call_hasher_impl_u64!(&NonZeroU32);
+call_hasher_impl_u64!(&NonZeroU64);
+call_hasher_impl_u64!(&NonZeroI8);
+call_hasher_impl_u64!(&NonZeroI16);
+call_hasher_impl_u64!(&NonZeroI32);
+call_hasher_impl_u64!(&NonZeroI64);
+call_hasher_impl_u64!(&(u32, u32));
 
 macro_rules! call_hasher_impl_fixed_length {
     ($typ:ty) => {
@@ -94,6 +126,35 @@ call_hasher_impl_fixed_length!(&u128);
 call_hasher_impl_fixed_length!(&i128);
 call_hasher_impl_fixed_length!(&usize);
 call_hasher_impl_fixed_length!(&isize);
+call_hasher_impl_fixed_length!(NonZeroU128);
+call_hasher_impl_fixed_length!(NonZeroI128);
+call_hasher_impl_fixed_length!(NonZeroUsize);
+call_hasher_impl_fixed_length!(NonZeroIsize);
+call_hasher_impl_fixed_length!(&NonZeroU128);
+call_hasher_impl_fixed_length!(&NonZeroI128);
+call_hasher_impl_fixed_length!(&NonZeroUsize);
+call_hasher_impl_fixed_length!(&NonZeroIsize);
+
+// The `Hash` impls of these types are a mix of integer writes (and are std implementation details), so they use the
+// full hasher with only the cheaper finish.
+#[cfg(all(specialize, feature = "std"))]
+mod net_types {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    call_hasher_impl_fixed_length!(IpAddr);
+    call_hasher_impl_fixed_length!(Ipv4Addr);
+    call_hasher_impl_fixed_length!(Ipv6Addr);
+    call_hasher_impl_fixed_length!(SocketAddr);
+    call_hasher_impl_fixed_length!(SocketAddrV4);
+    call_hasher_impl_fixed_length!(SocketAddrV6);
+    call_hasher_impl_fixed_length!(&IpAddr);
+    call_hasher_impl_fixed_length!(&Ipv4Addr);
+    call_hasher_impl_fixed_length!(&Ipv6Addr);
+    call_hasher_impl_fixed_length!(&SocketAddr);
+    call_hasher_impl_fixed_length!(&SocketAddrV4);
+    call_hasher_impl_fixed_length!(&SocketAddrV6);
+}
 
 #[cfg(specialize)]
 impl CallHasher for [u8] {
@@ -127,6 +188,49 @@ impl CallHasher for String {
     }
 }
 
+#[cfg(specialize)]
+impl<const N: usize> CallHasher for [u8; N] {
+    #[inline]
+    fn get_hash<H: Hash + ?Sized>(value: &H, random_state: &RandomState) -> u64 {
+        random_state.hash_as_str(value)
+    }
+}
+
+// Note that references to string types (such as `&str` or `&&str`) are deliberately not specialized. Maps hash keys
+// and lookup values by reference, and a reference to any user type implementing `Borrow<str>` must hash the same as
+// `&str`, which can only be guaranteed if they both use the generic path.
+macro_rules! call_hasher_impl_str {
+    ($typ:ty) => {
+        #[cfg(specialize)]
+        impl CallHasher for $typ {
+            #[inline]
+            fn get_hash<H: Hash + ?Sized>(value: &H, random_state: &RandomState) -> u64 {
+                random_state.hash_as_str(value)
+            }
+        }
+    };
+}
+
+call_hasher_impl_str!(Box<str>);
+call_hasher_impl_str!(Rc<str>);
+call_hasher_impl_str!(Arc<str>);
+call_hasher_impl_str!(Cow<'_, str>);
+call_hasher_impl_str!(Box<[u8]>);
+
+// Paths and OS strings are variable length (and often untrusted), so they use the string hasher, which handles the
+// mix of byte and length writes their `Hash` impls make.
+#[cfg(all(specialize, feature = "std"))]
+mod path_types {
+    use super::*;
+    use std::ffi::{OsStr, OsString};
+    use std::path::{Path, PathBuf};
+
+    call_hasher_impl_str!(Path);
+    call_hasher_impl_str!(PathBuf);
+    call_hasher_impl_str!(OsStr);
+    call_hasher_impl_str!(OsString);
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -142,6 +246,71 @@ mod test {
         assert_ne!(hasher.finish(), shortened);
     }
 
+    /// The hash of `value` without specialization.
+    #[cfg(specialize)]
+    fn generic_hash<T: Hash + ?Sized>(value: &T, build_hasher: &RandomState) -> u64 {
+        let mut hasher = build_hasher.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    #[cfg(all(specialize, feature = "std"))]
+    pub fn test_additional_types_specialized() {
+        use core::num::*;
+        use std::borrow::Cow;
+        use std::ffi::{OsStr, OsString};
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+        use std::path::{Path, PathBuf};
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let build_hasher = RandomState::with_seeds(1, 2, 3, 4);
+        macro_rules! assert_specialized {
+            ($typ:ty, $value:expr) => {
+                let value: &$typ = $value;
+                assert_ne!(
+                    <$typ>::get_hash(value, &build_hasher),
+                    generic_hash(value, &build_hasher),
+                    "{} is not specialized",
+                    stringify!($typ)
+                );
+            };
+        }
+        assert_specialized!(char, &'a');
+        assert_specialized!(&char, &&'a');
+        assert_specialized!(bool, &true);
+        assert_specialized!(NonZeroU8, &NonZeroU8::new(7).unwrap());
+        assert_specialized!(NonZeroI32, &NonZeroI32::new(7).unwrap());
+        assert_specialized!(NonZeroU64, &NonZeroU64::new(7).unwrap());
+        assert_specialized!(NonZeroU128, &NonZeroU128::new(7).unwrap());
+        assert_specialized!(NonZeroUsize, &NonZeroUsize::new(7).unwrap());
+        assert_specialized!((u32, u32), &(1, 2));
+        assert_specialized!([u8; 4], &[1, 2, 3, 4]);
+        assert_specialized!(Box<str>, &Box::from("test"));
+        assert_specialized!(Rc<str>, &Rc::from("test"));
+        assert_specialized!(Arc<str>, &Arc::from("test"));
+        assert_specialized!(Cow<str>, &Cow::Borrowed("test"));
+        assert_specialized!(Box<[u8]>, &Box::from(&b"test"[..]));
+        assert_specialized!(Path, Path::new("/tmp/a"));
+        assert_specialized!(PathBuf, &PathBuf::from("/tmp/a"));
+        assert_specialized!(OsStr, OsStr::new("test"));
+        assert_specialized!(OsString, &OsString::from("test"));
+        assert_specialized!(IpAddr, &IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_specialized!(Ipv6Addr, &Ipv6Addr::LOCALHOST);
+        assert_specialized!(SocketAddr, &SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 80));
+
+        // Paths and OS strings take the string path.
+        assert_eq!(
+            OsStr::get_hash(OsStr::new("test"), &build_hasher),
+            build_hasher.hash_as_str(OsStr::new("test"))
+        );
+        assert_eq!(
+            Path::get_hash(Path::new("/tmp/a"), &build_hasher),
+            build_hasher.hash_as_str(Path::new("/tmp/a"))
+        );
+    }
+
     /// Tests that some non-trivial transformation takes place.
     #[test]
     pub fn test_input_processed() {
@@ -169,6 +338,154 @@ mod test {
         assert_ne!(5, xored);
     }
 
+    #[test]
+    pub fn test_additional_types_consistent() {
+        use core::num::*;
+        use std::borrow::Cow;
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let build_hasher = RandomState::with_seeds(1, 2, 3, 4);
+        let expected = str::get_hash("test", &build_hasher);
+        assert_eq!(<Box<str>>::get_hash(&Box::<str>::from("test"), &build_hasher), expected);
+        assert_eq!(<Rc<str>>::get_hash(&Rc::<str>::from("test"), &build_hasher), expected);
+        assert_eq!(<Arc<str>>::get_hash(&Arc::<str>::from("test"), &build_hasher), expected);
+        assert_eq!(
+            <Cow<str>>::get_hash(&Cow::<str>::Borrowed("test"), &build_hasher),
+            expected
+        );
+        assert_eq!(
+            <Cow<str>>::get_hash(&Cow::<str>::Owned("test".to_string()), &build_hasher),
+            expected
+        );
+
+        let bytes = [1_u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        let expected = <[u8]>::get_hash(&bytes[..], &build_hasher);
+        assert_eq!(<[u8; 16]>::get_hash(&bytes, &build_hasher), expected);
+        assert_eq!(
+            <Box<[u8]>>::get_hash(&Box::<[u8]>::from(&bytes[..]), &build_hasher),
+            expected
+        );
+        assert_eq!(<Vec<u8>>::get_hash(&bytes.to_vec(), &build_hasher), expected);
+
+        let value = NonZeroU64::new(7).unwrap();
+        assert_eq!(
+            NonZeroU64::get_hash(&value, &build_hasher),
+            u64::get_hash(&7_u64, &build_hasher)
+        );
+        assert_eq!(
+            <&NonZeroU64>::get_hash(&&value, &build_hasher),
+            u64::get_hash(&7_u64, &build_hasher)
+        );
+        let value = NonZeroU32::new(7).unwrap();
+        assert_eq!(
+            NonZeroU32::get_hash(&value, &build_hasher),
+            u32::get_hash(&7_u32, &build_hasher)
+        );
+        let value = NonZeroU128::new(7).unwrap();
+        assert_eq!(
+            NonZeroU128::get_hash(&value, &build_hasher),
+            u128::get_hash(&7_u128, &build_hasher)
+        );
+        let value = NonZeroUsize::new(7).unwrap();
+        assert_eq!(
+            NonZeroUsize::get_hash(&value, &build_hasher),
+            usize::get_hash(&7_usize, &build_hasher)
+        );
+
+        assert_eq!(
+            char::get_hash(&'a', &build_hasher),
+            u32::get_hash(&('a' as u32), &build_hasher)
+        );
+        assert_eq!(
+            <&char>::get_hash(&&'a', &build_hasher),
+            char::get_hash(&'a', &build_hasher)
+        );
+        assert_eq!(bool::get_hash(&true, &build_hasher), u8::get_hash(&1_u8, &build_hasher));
+        assert_ne!(
+            bool::get_hash(&true, &build_hasher),
+            bool::get_hash(&false, &build_hasher)
+        );
+        assert_eq!(
+            <(u32, u32)>::get_hash(&(1_u32, 2_u32), &build_hasher),
+            <&(u32, u32)>::get_hash(&&(1_u32, 2_u32), &build_hasher)
+        );
+        assert_ne!(
+            <(u32, u32)>::get_hash(&(1_u32, 2_u32), &build_hasher),
+            <(u32, u32)>::get_hash(&(2_u32, 1_u32), &build_hasher)
+        );
+    }
+
+    #[test]
+    pub fn test_std_types_consistent() {
+        use std::ffi::{OsStr, OsString};
+        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+        use std::path::{Path, PathBuf};
+
+        let build_hasher = RandomState::with_seeds(1, 2, 3, 4);
+        let path = Path::new("/tmp/a/b");
+        assert_eq!(
+            Path::get_hash(path, &build_hasher),
+            PathBuf::get_hash(&path.to_path_buf(), &build_hasher)
+        );
+        assert_eq!(
+            Path::get_hash(path, &build_hasher),
+            Path::get_hash(Path::new("/tmp//a/./b"), &build_hasher)
+        );
+        assert_ne!(
+            Path::get_hash(path, &build_hasher),
+            Path::get_hash(Path::new("/tmp/ab"), &build_hasher)
+        );
+        let os = OsStr::new("test");
+        assert_eq!(
+            OsStr::get_hash(os, &build_hasher),
+            OsString::get_hash(&os.to_os_string(), &build_hasher)
+        );
+
+        let ip = Ipv4Addr::new(127, 0, 0, 1);
+        assert_eq!(
+            Ipv4Addr::get_hash(&ip, &build_hasher),
+            <&Ipv4Addr>::get_hash(&&ip, &build_hasher)
+        );
+        assert_ne!(
+            Ipv4Addr::get_hash(&ip, &build_hasher),
+            Ipv4Addr::get_hash(&Ipv4Addr::new(127, 0, 0, 2), &build_hasher)
+        );
+        assert_ne!(
+            IpAddr::get_hash(&IpAddr::V4(ip), &build_hasher),
+            IpAddr::get_hash(&IpAddr::V6(ip.to_ipv6_mapped()), &build_hasher)
+        );
+        let a = SocketAddr::new(IpAddr::V4(ip), 80);
+        let b = SocketAddr::new(IpAddr::V4(ip), 81);
+        assert_eq!(
+            SocketAddr::get_hash(&a, &build_hasher),
+            <&SocketAddr>::get_hash(&&a, &build_hasher)
+        );
+        assert_ne!(
+            SocketAddr::get_hash(&a, &build_hasher),
+            SocketAddr::get_hash(&b, &build_hasher)
+        );
+    }
+
+    /// Keys and lookups go through references, so borrowed lookups must agree with the stored keys.
+    #[test]
+    pub fn test_borrowed_lookups() {
+        use std::sync::Arc;
+
+        let mut map: crate::HashMap<Box<str>, u32> = crate::HashMap::default();
+        map.insert(Box::from("test"), 1);
+        assert_eq!(map.get("test"), Some(&1));
+        let mut map: crate::HashMap<&str, u32> = crate::HashMap::default();
+        map.insert("test", 1);
+        assert_eq!(map.get("test"), Some(&1));
+        let mut map: crate::HashMap<Arc<str>, u32> = crate::HashMap::default();
+        map.insert(Arc::from("test"), 1);
+        assert_eq!(map.get("test"), Some(&1));
+        let mut map: crate::HashMap<std::path::PathBuf, u32> = crate::HashMap::default();
+        map.insert("/tmp/a".into(), 1);
+        assert_eq!(map.get(std::path::Path::new("/tmp/a")), Some(&1));
+    }
+
     #[test]
     pub fn test_ref_independent() {
         let build_hasher = RandomState::with_seeds(1, 2, 3, 4);
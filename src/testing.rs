@@ -0,0 +1,114 @@
+//! Utilities for making hashing reproducible in tests. (Requires the `test-determinism` feature.)
+//!
+//! Because [RandomState::new] uses random keys, the iteration order of an [AHashMap](crate::AHashMap) differs from
+//! run to run. This breaks tests which compare against recorded output. [with_deterministic_seeds] overrides the
+//! keys for the duration of a closure, so that tests can be made reproducible without changing the code under test.
+//!
+//! This feature should only be enabled as a dev-dependency. Outside of a call to [with_deterministic_seeds] the
+//! behaviour of aHash is unchanged.
+//!
+//! # Example
+//! ```
+//! use ahash::testing::with_deterministic_seeds;
+//! use ahash::AHashMap;
+//!
+//! let build = || {
+//!     let map: AHashMap<u32, u32> = (0..100).map(|i| (i, i)).collect();
+//!     map.keys().copied().collect::<Vec<_>>()
+//! };
+//! assert_eq!(with_deterministic_seeds(42, build), with_deterministic_seeds(42, build));
+//! ```
+use crate::RandomState;
+use core::cell::Cell;
+
+std::thread_local! {
+    static DETERMINISTIC: Cell<Option<(u64, u64)>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with [RandomState::new] (and therefore `RandomState::default()`, `AHashMap::new()`, etc.) deriving keys
+/// from `seed` plus a counter, instead of from the [RandomSource](crate::random_state::RandomSource).
+///
+/// The counter starts at zero each time this is called, so the `n`th `RandomState` created inside `f` always has the
+/// same keys for the same seed. Each `RandomState` still has different keys from the others.
+///
+/// The override only applies to the calling thread. Calls may be nested; the previous setting is restored when `f`
+/// returns or panics.
+///
+/// **These keys are not random and must never be used to hash untrusted input.**
+pub fn with_deterministic_seeds<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<(u64, u64)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DETERMINISTIC.with(|d| d.set(self.0));
+        }
+    }
+
+    let _restore = Restore(DETERMINISTIC.with(|d| d.replace(Some((seed, 0)))));
+    f()
+}
+
+/// Returns true if the calling thread is inside [with_deterministic_seeds].
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.with(|d| d.get().is_some())
+}
+
+/// Returns the next deterministic `RandomState` if an override is active on this thread.
+pub(crate) fn next_deterministic_state() -> Option<RandomState> {
+    DETERMINISTIC.with(|d| {
+        let (seed, counter) = d.get()?;
+        d.set(Some((seed, counter.wrapping_add(1))));
+        Some(RandomState::with_seeds(seed, 0, 0, 0).derive(&counter))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AHashMap;
+
+    fn hashes() -> [u64; 3] {
+        [
+            RandomState::new().hash_one(1_u64),
+            RandomState::default().hash_one(1_u64),
+            AHashMap::<u64, u64>::new().hasher().hash_one(1_u64),
+        ]
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let a = with_deterministic_seeds(1, hashes);
+        let b = with_deterministic_seeds(1, hashes);
+        let c = with_deterministic_seeds(2, hashes);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a[0], a[1]);
+        assert_ne!(a[1], a[2]);
+    }
+
+    #[test]
+    fn test_scoped() {
+        assert!(!is_deterministic());
+        let (outer, inner) = with_deterministic_seeds(1, || {
+            let inner = with_deterministic_seeds(2, hashes);
+            assert!(is_deterministic());
+            (hashes(), inner)
+        });
+        assert!(!is_deterministic());
+        assert_eq!(outer, with_deterministic_seeds(1, hashes));
+        assert_eq!(inner, with_deterministic_seeds(2, hashes));
+        assert_ne!(hashes(), hashes());
+
+        let result = std::panic::catch_unwind(|| with_deterministic_seeds(3, || panic!("test")));
+        assert!(result.is_err());
+        assert!(!is_deterministic());
+    }
+
+    #[test]
+    fn test_other_threads_unaffected() {
+        with_deterministic_seeds(1, || {
+            let other = std::thread::spawn(|| (is_deterministic(), hashes())).join().unwrap();
+            assert!(!other.0);
+            assert_ne!(other.1, with_deterministic_seeds(1, hashes));
+        });
+    }
+}
@@ -0,0 +1,691 @@
+//! Recording and replaying the calls made to a [Hasher]. (Requires the `trace` feature.)
+//!
+//! Which hasher is fastest depends heavily on what is being hashed. [TracingBuildHasher] wraps any [BuildHasher]
+//! and records every `write_*` and `finish` call made by the program using it, so that the real workload can later
+//! be fed to other hashers with [replay] and timed.
+//!
+//! # Example
+//! ```
+//! use ahash::trace::{replay, TracingBuildHasher};
+//! use ahash::RandomState;
+//! use std::collections::HashMap;
+//!
+//! let builder = TracingBuildHasher::new(RandomState::new(), Vec::new()).unwrap();
+//! let mut map = HashMap::with_hasher(builder.clone());
+//! map.insert("a", 1);
+//! map.insert("b", 2);
+//! drop(map);
+//! let trace = builder.into_inner().unwrap().unwrap();
+//!
+//! let stats = replay(&trace[..], RandomState::new()).unwrap();
+//! assert_eq!(stats.calls.finish, 2);
+//! ```
+//!
+//! # Format
+//!
+//! A trace starts with the 7 byte magic value `AHTRACE` followed by a one byte format version ([FORMAT_VERSION]).
+//! The rest of the trace is a sequence of records. Each record is a one byte code followed by its operand, with
+//! integers encoded as little endian:
+//!
+//! | Code  | Call          | Operand                                 |
+//! |-------|---------------|-----------------------------------------|
+//! | `'1'` | `write_u8`    | `u8`                                    |
+//! | `'2'` | `write_u16`   | `u16`                                   |
+//! | `'4'` | `write_u32`   | `u32`                                   |
+//! | `'8'` | `write_u64`   | `u64`                                   |
+//! | `'B'` | `write_u128`  | `u128`                                  |
+//! | `'u'` | `write_usize` | `u64`                                   |
+//! | `'s'` | `write`       | the length as a `u64`, then the bytes   |
+//! | `'f'` | `finish`      | none                                    |
+//!
+//! Signed writes are recorded as the unsigned write of the same width. A hasher's calls are buffered and written to the
+//! sink together when `finish` is called, so the calls of hashers used concurrently are never interleaved. Calls made
+//! on a hasher which is dropped without calling `finish` are not recorded. If `finish` is called more than once all of
+//! the calls made up to that point are recorded again, so each `'f'` marks the end of one complete hash computation.
+use core::fmt;
+use core::hash::{BuildHasher, Hasher};
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+/// The value a trace starts with, before the version.
+pub const MAGIC: &[u8; 7] = b"AHTRACE";
+
+/// The version of the trace format written by [TracingBuildHasher] and read by [replay].
+pub const FORMAT_VERSION: u8 = 1;
+
+const WRITE_U8: u8 = b'1';
+const WRITE_U16: u8 = b'2';
+const WRITE_U32: u8 = b'4';
+const WRITE_U64: u8 = b'8';
+const WRITE_U128: u8 = b'B';
+const WRITE_USIZE: u8 = b'u';
+const WRITE: u8 = b's';
+const FINISH: u8 = b'f';
+
+/// The destination shared by a [TracingBuildHasher] and its hashers.
+struct Sink<W> {
+    writer: W,
+    /// The first error encountered writing to `writer`. Once set nothing more is recorded.
+    error: Option<io::Error>,
+}
+
+/// A [BuildHasher] that records the calls made to its hashers.
+///
+/// Hashing is performed by the wrapped `BuildHasher`, so this can be dropped into a program without changing its
+/// behaviour. Clones share the same sink, so a single trace can cover all the maps in a program.
+///
+/// Hashers cannot report errors, so if writing to the sink fails recording stops and the error is returned by the
+/// next call to [flush](Self::flush) or [into_inner](Self::into_inner).
+pub struct TracingBuildHasher<B, W: Write> {
+    inner: B,
+    sink: Arc<Mutex<Sink<W>>>,
+}
+
+impl<B, W: Write> TracingBuildHasher<B, W> {
+    /// Wraps `inner`, recording to `writer`. The header is written immediately.
+    ///
+    /// The calls made to compute each hash are a separate small write, so `writer` should usually be buffered.
+    pub fn new(inner: B, mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION])?;
+        Ok(TracingBuildHasher {
+            inner,
+            sink: Arc::new(Mutex::new(Sink { writer, error: None })),
+        })
+    }
+
+    /// Flushes the sink, or returns the error which stopped recording.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut sink = self.sink.lock().unwrap();
+        if let Some(error) = sink.error.take() {
+            return Err(error);
+        }
+        sink.writer.flush()
+    }
+
+    /// Flushes and returns the sink.
+    ///
+    /// The sink is shared with any clones of this builder and the hashers created by them. If any of these still exist
+    /// the sink is flushed but not returned, and `Ok(None)` is returned.
+    pub fn into_inner(self) -> io::Result<Option<W>> {
+        self.flush()?;
+        Ok(Arc::try_unwrap(self.sink)
+            .ok()
+            .map(|sink| sink.into_inner().unwrap().writer))
+    }
+}
+
+impl<B: Clone, W: Write> Clone for TracingBuildHasher<B, W> {
+    fn clone(&self) -> Self {
+        TracingBuildHasher {
+            inner: self.inner.clone(),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<B: fmt::Debug, W: Write> fmt::Debug for TracingBuildHasher<B, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracingBuildHasher")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B: BuildHasher, W: Write> BuildHasher for TracingBuildHasher<B, W> {
+    type Hasher = TracingHasher<B::Hasher, W>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        TracingHasher {
+            inner: self.inner.build_hasher(),
+            calls: Vec::new(),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+/// The [Hasher] created by a [TracingBuildHasher].
+pub struct TracingHasher<H, W: Write> {
+    inner: H,
+    /// The encoded calls made so far, written to the sink by `finish`.
+    calls: Vec<u8>,
+    sink: Arc<Mutex<Sink<W>>>,
+}
+
+impl<H, W: Write> TracingHasher<H, W> {
+    #[inline]
+    fn record(&mut self, code: u8, operand: &[u8]) {
+        self.calls.push(code);
+        self.calls.extend_from_slice(operand);
+    }
+}
+
+impl<H: Clone, W: Write> Clone for TracingHasher<H, W> {
+    fn clone(&self) -> Self {
+        TracingHasher {
+            inner: self.inner.clone(),
+            calls: self.calls.clone(),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<H: fmt::Debug, W: Write> fmt::Debug for TracingHasher<H, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracingHasher")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H: Hasher, W: Write> Hasher for TracingHasher<H, W> {
+    fn finish(&self) -> u64 {
+        let mut sink = self.sink.lock().unwrap();
+        if sink.error.is_none() {
+            let sink = &mut *sink;
+            if let Err(error) = sink
+                .writer
+                .write_all(&self.calls)
+                .and_then(|_| sink.writer.write_all(&[FINISH]))
+            {
+                sink.error = Some(error);
+            }
+        }
+        self.inner.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.record(WRITE, &(bytes.len() as u64).to_le_bytes());
+        self.calls.extend_from_slice(bytes);
+        self.inner.write(bytes);
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.record(WRITE_U8, &[i]);
+        self.inner.write_u8(i);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.record(WRITE_U16, &i.to_le_bytes());
+        self.inner.write_u16(i);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.record(WRITE_U32, &i.to_le_bytes());
+        self.inner.write_u32(i);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.record(WRITE_U64, &i.to_le_bytes());
+        self.inner.write_u64(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.record(WRITE_U128, &i.to_le_bytes());
+        self.inner.write_u128(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.record(WRITE_USIZE, &(i as u64).to_le_bytes());
+        self.inner.write_usize(i);
+    }
+}
+
+/// An error reading a trace.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TraceError {
+    /// Reading the trace failed.
+    Io(io::Error),
+    /// The trace does not start with [MAGIC].
+    BadMagic,
+    /// The trace was written in a version of the format this version of aHash cannot read.
+    UnsupportedVersion(u8),
+    /// A record had an unknown code.
+    UnknownCode {
+        /// The code.
+        code: u8,
+        /// The position of the code in the trace.
+        offset: u64,
+    },
+    /// The trace ended part way through a record, or with calls that were not followed by a `finish`.
+    Truncated {
+        /// The position of the start of the incomplete record.
+        offset: u64,
+    },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::Io(error) => write!(f, "error reading trace: {}", error),
+            TraceError::BadMagic => f.write_str("not an aHash trace"),
+            TraceError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported trace format version {} (expected {})",
+                version, FORMAT_VERSION
+            ),
+            TraceError::UnknownCode { code, offset } => {
+                write!(f, "unknown record code {:#04x} at offset {}", code, offset)
+            }
+            TraceError::Truncated { offset } => write!(f, "trace is truncated at offset {}", offset),
+        }
+    }
+}
+
+impl Error for TraceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TraceError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TraceError {
+    fn from(error: io::Error) -> Self {
+        TraceError::Io(error)
+    }
+}
+
+/// The number of calls of each type in a trace. Each field counts calls of the [Hasher] method of the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallCounts {
+    pub write: u64,
+    pub write_u8: u64,
+    pub write_u16: u64,
+    pub write_u32: u64,
+    pub write_u64: u64,
+    pub write_u128: u64,
+    pub write_usize: u64,
+    pub finish: u64,
+}
+
+impl CallCounts {
+    /// The total number of calls of all types.
+    pub fn total(&self) -> u64 {
+        self.write
+            + self.write_u8
+            + self.write_u16
+            + self.write_u32
+            + self.write_u64
+            + self.write_u128
+            + self.write_usize
+            + self.finish
+    }
+}
+
+/// A histogram of sizes in bytes, with power of two buckets.
+///
+/// Bucket 0 counts sizes of 0, and bucket `i > 0` counts sizes from `2^(i-1)` up to `2^i - 1`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeHistogram {
+    buckets: Vec<u64>,
+}
+
+impl SizeHistogram {
+    /// Adds a size to the histogram.
+    pub fn add(&mut self, size: u64) {
+        let bucket = (64 - size.leading_zeros()) as usize;
+        if self.buckets.len() <= bucket {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+    }
+
+    /// The count in each bucket, up to the last non-empty bucket.
+    pub fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    /// The total of all the counts.
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Returns the smallest and largest sizes counted by the given bucket.
+    pub fn bucket_range(bucket: usize) -> (u64, u64) {
+        match bucket {
+            0 => (0, 0),
+            _ => (1 << (bucket - 1), u64::MAX >> (64 - bucket)),
+        }
+    }
+}
+
+/// The result of replaying a trace.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    /// The number of calls of each type.
+    pub calls: CallCounts,
+    /// The total size of the data hashed by each hash computation (that is, each `finish`). The integer writes count
+    /// as their width in bytes, and `write_usize` as 8.
+    pub key_sizes: SizeHistogram,
+    /// The lengths passed to `write`.
+    pub write_lengths: SizeHistogram,
+    /// The time taken to replay the calls. This does not include reading or decoding the trace.
+    pub elapsed: Duration,
+    /// The wrapping sum of the values returned by `finish`. This prevents the replay from being optimized away, and
+    /// allows checking two replays with the same hasher produced the same hashes.
+    pub checksum: u64,
+}
+
+impl Stats {
+    /// The number of hashes computed per second.
+    pub fn hashes_per_second(&self) -> f64 {
+        self.calls.finish as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// A decoded call.
+enum Call {
+    Write { start: usize, end: usize },
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(u64),
+    Finish,
+}
+
+/// Reads exactly `N` bytes, treating EOF as truncation of the record starting at `offset`.
+fn read_array<R: Read, const N: usize>(input: &mut R, offset: u64) -> Result<[u8; N], TraceError> {
+    let mut buf = [0; N];
+    input.read_exact(&mut buf).map_err(|error| match error.kind() {
+        io::ErrorKind::UnexpectedEof => TraceError::Truncated { offset },
+        _ => TraceError::Io(error),
+    })?;
+    Ok(buf)
+}
+
+/// Reads a trace, returning the calls, with the data written by `write` stored in `data`.
+fn decode<R: Read>(mut input: R, data: &mut Vec<u8>, stats: &mut Stats) -> Result<Vec<Call>, TraceError> {
+    let magic: [u8; 7] = read_array(&mut input, 0).map_err(|error| match error {
+        TraceError::Truncated { .. } => TraceError::BadMagic,
+        error => error,
+    })?;
+    if &magic != MAGIC {
+        return Err(TraceError::BadMagic);
+    }
+    let [version] = read_array(&mut input, 0).map_err(|_| TraceError::BadMagic)?;
+    if version != FORMAT_VERSION {
+        return Err(TraceError::UnsupportedVersion(version));
+    }
+
+    let mut calls = Vec::new();
+    let mut offset = (MAGIC.len() + 1) as u64;
+    let mut start = offset;
+    let mut key_size = 0_u64;
+    loop {
+        let mut code = [0];
+        match input.read(&mut code) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(TraceError::Io(error)),
+        }
+        let (call, size) = match code[0] {
+            WRITE_U8 => (Call::U8(u8::from_le_bytes(read_array(&mut input, offset)?)), 1),
+            WRITE_U16 => (Call::U16(u16::from_le_bytes(read_array(&mut input, offset)?)), 2),
+            WRITE_U32 => (Call::U32(u32::from_le_bytes(read_array(&mut input, offset)?)), 4),
+            WRITE_U64 => (Call::U64(u64::from_le_bytes(read_array(&mut input, offset)?)), 8),
+            WRITE_U128 => (Call::U128(u128::from_le_bytes(read_array(&mut input, offset)?)), 16),
+            WRITE_USIZE => (Call::Usize(u64::from_le_bytes(read_array(&mut input, offset)?)), 8),
+            WRITE => {
+                let len = u64::from_le_bytes(read_array(&mut input, offset)?);
+                let begin = data.len();
+                let read = (&mut input).take(len).read_to_end(data)?;
+                if (read as u64) < len {
+                    return Err(TraceError::Truncated { offset });
+                }
+                stats.write_lengths.add(len);
+                (
+                    Call::Write {
+                        start: begin,
+                        end: data.len(),
+                    },
+                    len,
+                )
+            }
+            FINISH => {
+                stats.key_sizes.add(key_size);
+                key_size = 0;
+                start = offset + 1;
+                (Call::Finish, 0)
+            }
+            code => return Err(TraceError::UnknownCode { code, offset }),
+        };
+        let counter = match call {
+            Call::Write { .. } => &mut stats.calls.write,
+            Call::U8(_) => &mut stats.calls.write_u8,
+            Call::U16(_) => &mut stats.calls.write_u16,
+            Call::U32(_) => &mut stats.calls.write_u32,
+            Call::U64(_) => &mut stats.calls.write_u64,
+            Call::U128(_) => &mut stats.calls.write_u128,
+            Call::Usize(_) => &mut stats.calls.write_usize,
+            Call::Finish => &mut stats.calls.finish,
+        };
+        *counter += 1;
+        key_size += size;
+        offset += 1 + match call {
+            Call::Write { .. } => 8 + size,
+            _ => size,
+        };
+        calls.push(call);
+    }
+    if !matches!(calls.last(), None | Some(Call::Finish)) {
+        return Err(TraceError::Truncated { offset: start });
+    }
+    Ok(calls)
+}
+
+/// Replays a trace recorded by [TracingBuildHasher] using hashers built by `builder`.
+///
+/// The whole trace is read and decoded before any hashing is done, so [Stats::elapsed] only measures the hasher.
+/// After each `finish` the next calls are made on a new hasher.
+pub fn replay<R: Read, B: BuildHasher>(trace: R, builder: B) -> Result<Stats, TraceError> {
+    let mut stats = Stats::default();
+    let mut data = Vec::new();
+    let calls = decode(trace, &mut data, &mut stats)?;
+
+    let start = Instant::now();
+    let mut checksum = 0_u64;
+    let mut hasher = builder.build_hasher();
+    for call in &calls {
+        match *call {
+            Call::Write { start, end } => hasher.write(&data[start..end]),
+            Call::U8(i) => hasher.write_u8(i),
+            Call::U16(i) => hasher.write_u16(i),
+            Call::U32(i) => hasher.write_u32(i),
+            Call::U64(i) => hasher.write_u64(i),
+            Call::U128(i) => hasher.write_u128(i),
+            Call::Usize(i) => hasher.write_usize(i as usize),
+            Call::Finish => {
+                checksum = checksum.wrapping_add(hasher.finish());
+                hasher = builder.build_hasher();
+            }
+        }
+    }
+    stats.elapsed = start.elapsed();
+    stats.checksum = checksum;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RandomState;
+    use core::hash::Hash;
+
+    fn record(f: impl FnOnce(&TracingBuildHasher<RandomState, Vec<u8>>)) -> Vec<u8> {
+        let builder = TracingBuildHasher::new(RandomState::with_seeds(1, 2, 3, 4), Vec::new()).unwrap();
+        f(&builder);
+        builder.into_inner().unwrap().unwrap()
+    }
+
+    /// Hashes without the specialization `RandomState::hash_one` may use.
+    fn unspecialized<T: Hash>(state: &RandomState, value: T) -> u64 {
+        let mut hasher = state.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_recording_does_not_change_hashes() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        record(|builder| {
+            assert_eq!(builder.hash_one("hello"), unspecialized(&state, "hello"));
+            assert_eq!(builder.hash_one(12345_u64), unspecialized(&state, 12345_u64));
+            assert_eq!(
+                builder.hash_one((1_u8, -2_i128)),
+                unspecialized(&state, (1_u8, -2_i128))
+            );
+        });
+    }
+
+    #[test]
+    fn test_replay_reproduces_hashes() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        let mut expected = 0_u64;
+        let trace = record(|builder| {
+            let mut hasher = builder.build_hasher();
+            hasher.write_u8(1);
+            hasher.write_u16(2);
+            hasher.write_u32(3);
+            hasher.write_u64(4);
+            hasher.write_u128(5);
+            hasher.write_usize(6);
+            hasher.write(b"seven");
+            expected = expected.wrapping_add(hasher.finish());
+            expected = expected.wrapping_add(builder.hash_one("eight"));
+            expected = expected.wrapping_add(builder.hash_one(vec![9_u32; 100]));
+        });
+
+        let stats = replay(&trace[..], state).unwrap();
+        assert_eq!(stats.checksum, expected);
+        assert_eq!(
+            stats.calls,
+            CallCounts {
+                write: 3,
+                write_u8: 2,
+                write_u16: 1,
+                write_u32: 1,
+                write_u64: 1,
+                write_u128: 1,
+                write_usize: 2,
+                finish: 3,
+            }
+        );
+        assert_eq!(stats.calls.total(), 14);
+        // 1 + 2 + 4 + 8 + 16 + 8 + 5 bytes, "eight" plus its terminator, and the Vec's length plus 400 bytes.
+        assert_eq!(stats.key_sizes.buckets(), &[0, 0, 0, 1, 0, 0, 1, 0, 0, 1]);
+        assert_eq!(stats.key_sizes.count(), 3);
+        assert_ne!(
+            replay(&trace[..], RandomState::with_seeds(1, 2, 3, 5))
+                .unwrap()
+                .checksum,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_unfinished_hashers_are_not_recorded() {
+        let trace = record(|builder| {
+            let mut hasher = builder.build_hasher();
+            hasher.write_u64(1);
+            let mut other = builder.build_hasher();
+            "interleaved".hash(&mut other);
+            let _ = other.finish();
+            drop(hasher);
+        });
+        let stats = replay(&trace[..], RandomState::new()).unwrap();
+        assert_eq!(stats.calls.write_u64, 0);
+        assert_eq!(stats.calls.finish, 1);
+    }
+
+    #[test]
+    fn test_clones_share_the_sink() {
+        let builder = TracingBuildHasher::new(RandomState::new(), Vec::new()).unwrap();
+        let clone = builder.clone();
+        clone.hash_one(1_u32);
+        assert!(builder.clone().into_inner().unwrap().is_none());
+        drop(clone);
+        builder.hash_one(2_u32);
+        let trace = builder.into_inner().unwrap().unwrap();
+        assert_eq!(replay(&trace[..], RandomState::new()).unwrap().calls.finish, 2);
+    }
+
+    #[test]
+    fn test_write_errors_are_reported() {
+        struct Full;
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        assert!(TracingBuildHasher::new(RandomState::new(), Full).is_err());
+
+        let mut buf = [0_u8; 12];
+        let builder = TracingBuildHasher::new(RandomState::new(), &mut buf[..]).unwrap();
+        builder.hash_one(1_u64);
+        assert!(builder.flush().is_err());
+        assert!(builder.flush().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_traces() {
+        let trace = record(|builder| {
+            builder.hash_one("abc");
+        });
+        let header = MAGIC.len() + 1;
+        assert!(matches!(
+            replay(&b""[..], RandomState::new()),
+            Err(TraceError::BadMagic)
+        ));
+        assert!(matches!(
+            replay(&b"NOTATRACE"[..], RandomState::new()),
+            Err(TraceError::BadMagic)
+        ));
+
+        let mut bad_version = trace.clone();
+        bad_version[header - 1] = 99;
+        assert!(matches!(
+            replay(&bad_version[..], RandomState::new()),
+            Err(TraceError::UnsupportedVersion(99))
+        ));
+
+        let mut bad_code = trace.clone();
+        bad_code[header] = b'x';
+        match replay(&bad_code[..], RandomState::new()) {
+            Err(TraceError::UnknownCode { code, offset }) => assert_eq!((code, offset), (b'x', header as u64)),
+            other => panic!("{:?}", other),
+        }
+
+        for len in header + 1..trace.len() {
+            match replay(&trace[..len], RandomState::new()) {
+                Err(TraceError::Truncated { offset }) => assert!(offset >= header as u64 && offset < len as u64),
+                other => panic!("{}: {:?}", len, other),
+            }
+        }
+        assert!(replay(&trace[..header], RandomState::new()).is_ok());
+    }
+
+    #[test]
+    fn test_size_histogram() {
+        let mut histogram = SizeHistogram::default();
+        for size in [0, 1, 2, 3, 4, 7, 8, u64::MAX] {
+            histogram.add(size);
+        }
+        assert_eq!(&histogram.buckets()[..5], &[1, 1, 2, 2, 1]);
+        assert_eq!(histogram.buckets()[64], 1);
+        assert_eq!(SizeHistogram::bucket_range(0), (0, 0));
+        assert_eq!(SizeHistogram::bucket_range(3), (4, 7));
+        assert_eq!(SizeHistogram::bucket_range(64), (1 << 63, u64::MAX));
+    }
+}
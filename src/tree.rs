@@ -0,0 +1,292 @@
+//! Tree hashing for large buffers.
+//!
+//! Hashing a very large buffer with a single [AHasher](crate::AHasher) is inherently sequential. This module instead
+//! splits the input into fixed size leaves, hashes each leaf independently, and combines the results pairwise in a
+//! binary (Merkle) tree. Because the shape of the tree depends only on the length of the input and the leaf size,
+//! the result is the same whether the leaves are hashed on one thread or many.
+//!
+//! With the `rayon` feature enabled [hash_parallel] and [MerkleTree::new_parallel] use rayon's thread pool.
+//!
+//! Note that the result is *not* the same as hashing the buffer directly with an [AHasher](crate::AHasher).
+//!
+//! # Example
+//! ```
+//! use ahash::tree::{self, MerkleTree};
+//! use ahash::RandomState;
+//!
+//! let state = RandomState::with_seeds(1, 2, 3, 4);
+//! let mut data = vec![0_u8; 100_000];
+//! let mut merkle = MerkleTree::new(&state, &data, 4096);
+//! assert_eq!(merkle.root(), tree::hash(&state, &data, 4096));
+//!
+//! data[5000] = 1;
+//! merkle.update_leaf(1, &data[4096..8192]);
+//! assert_eq!(merkle.root(), tree::hash(&state, &data, 4096));
+//! ```
+use crate::RandomState;
+use core::hash::{BuildHasher, Hasher};
+use std::vec::Vec;
+
+const LEAF: u8 = 0;
+const NODE: u8 = 1;
+const ROOT: u8 = 2;
+
+#[inline]
+fn hash_leaf(state: &RandomState, index: usize, leaf: &[u8]) -> u64 {
+    let mut hasher = state.build_hasher();
+    hasher.write_u8(LEAF);
+    hasher.write_u64(index as u64);
+    hasher.write(leaf);
+    hasher.finish()
+}
+
+#[inline]
+fn hash_node(state: &RandomState, left: u64, right: u64) -> u64 {
+    let mut hasher = state.build_hasher();
+    hasher.write_u8(NODE);
+    hasher.write_u128(((right as u128) << 64) | left as u128);
+    hasher.finish()
+}
+
+#[inline]
+fn hash_root(state: &RandomState, top: u64, total_len: u64, leaf_size: usize) -> u64 {
+    let mut hasher = state.build_hasher();
+    hasher.write_u8(ROOT);
+    hasher.write_u64(top);
+    hasher.write_u64(total_len);
+    hasher.write_u64(leaf_size as u64);
+    hasher.finish()
+}
+
+/// Combines one level of the tree into the next. An odd node at the end is carried up unchanged.
+fn next_level(state: &RandomState, level: &[u64]) -> Vec<u64> {
+    level
+        .chunks(2)
+        .map(|pair| match *pair {
+            [left, right] => hash_node(state, left, right),
+            [single] => single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+fn next_level_parallel(state: &RandomState, level: &[u64]) -> Vec<u64> {
+    use rayon::prelude::*;
+    level
+        .par_chunks(2)
+        .map(|pair| match *pair {
+            [left, right] => hash_node(state, left, right),
+            [single] => single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+fn check_leaf_size(leaf_size: usize) {
+    assert!(leaf_size > 0, "leaf_size must be non-zero");
+}
+
+/// Computes the tree hash of `data` using leaves of `leaf_size` bytes on the current thread.
+///
+/// # Panics
+/// If `leaf_size` is zero.
+pub fn hash(state: &RandomState, data: &[u8], leaf_size: usize) -> u64 {
+    check_leaf_size(leaf_size);
+    let mut level: Vec<u64> = data
+        .chunks(leaf_size)
+        .enumerate()
+        .map(|(i, leaf)| hash_leaf(state, i, leaf))
+        .collect();
+    while level.len() > 1 {
+        level = next_level(state, &level);
+    }
+    hash_root(state, level.first().copied().unwrap_or(0), data.len() as u64, leaf_size)
+}
+
+/// Computes the tree hash of `data` using leaves of `leaf_size` bytes on rayon's thread pool.
+/// The result is identical to [hash].
+///
+/// # Panics
+/// If `leaf_size` is zero.
+#[cfg(feature = "rayon")]
+pub fn hash_parallel(state: &RandomState, data: &[u8], leaf_size: usize) -> u64 {
+    use rayon::prelude::*;
+    check_leaf_size(leaf_size);
+    let mut level: Vec<u64> = data
+        .par_chunks(leaf_size)
+        .enumerate()
+        .map(|(i, leaf)| hash_leaf(state, i, leaf))
+        .collect();
+    while level.len() > 1 {
+        level = next_level_parallel(state, &level);
+    }
+    hash_root(state, level.first().copied().unwrap_or(0), data.len() as u64, leaf_size)
+}
+
+/// A tree hash which retains its interior nodes so that the root can be recomputed cheaply when a leaf changes.
+///
+/// The root is always equal to [hash] of the current contents.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    state: RandomState,
+    leaf_size: usize,
+    total_len: u64,
+    levels: Vec<Vec<u64>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree for `data` using leaves of `leaf_size` bytes on the current thread.
+    ///
+    /// # Panics
+    /// If `leaf_size` is zero.
+    pub fn new(state: &RandomState, data: &[u8], leaf_size: usize) -> MerkleTree {
+        check_leaf_size(leaf_size);
+        let leaves = data
+            .chunks(leaf_size)
+            .enumerate()
+            .map(|(i, leaf)| hash_leaf(state, i, leaf))
+            .collect();
+        Self::from_leaves(state, leaves, data.len() as u64, leaf_size, next_level)
+    }
+
+    /// Builds the tree for `data` using leaves of `leaf_size` bytes on rayon's thread pool.
+    ///
+    /// # Panics
+    /// If `leaf_size` is zero.
+    #[cfg(feature = "rayon")]
+    pub fn new_parallel(state: &RandomState, data: &[u8], leaf_size: usize) -> MerkleTree {
+        use rayon::prelude::*;
+        check_leaf_size(leaf_size);
+        let leaves = data
+            .par_chunks(leaf_size)
+            .enumerate()
+            .map(|(i, leaf)| hash_leaf(state, i, leaf))
+            .collect();
+        Self::from_leaves(state, leaves, data.len() as u64, leaf_size, next_level_parallel)
+    }
+
+    fn from_leaves(
+        state: &RandomState,
+        leaves: Vec<u64>,
+        total_len: u64,
+        leaf_size: usize,
+        combine: fn(&RandomState, &[u64]) -> Vec<u64>,
+    ) -> MerkleTree {
+        let mut levels = vec![leaves];
+        while levels.last().map_or(false, |l| l.len() > 1) {
+            let next = combine(state, levels.last().unwrap());
+            levels.push(next);
+        }
+        MerkleTree {
+            state: state.clone(),
+            leaf_size,
+            total_len,
+            levels,
+        }
+    }
+
+    /// The number of leaves in the tree.
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// The size of each leaf in bytes. (The final leaf may be shorter.)
+    pub fn leaf_size(&self) -> usize {
+        self.leaf_size
+    }
+
+    /// The hash of the full contents.
+    pub fn root(&self) -> u64 {
+        let top = self.levels.last().and_then(|l| l.first()).copied().unwrap_or(0);
+        hash_root(&self.state, top, self.total_len, self.leaf_size)
+    }
+
+    /// Replaces the contents of the leaf at `index` and recomputes the path to the root.
+    /// This takes time proportional to the leaf size plus the log of the number of leaves.
+    ///
+    /// # Panics
+    /// If `index` is out of range or `data` is not the same length as the leaf it replaces.
+    pub fn update_leaf(&mut self, index: usize, data: &[u8]) {
+        let count = self.leaf_count();
+        assert!(index < count, "Leaf index {} out of range ({} leaves)", index, count);
+        let expected = if index == count - 1 {
+            (self.total_len - (self.leaf_size as u64 * (count as u64 - 1))) as usize
+        } else {
+            self.leaf_size
+        };
+        assert_eq!(data.len(), expected, "Replacement leaf must have the same length");
+        self.levels[0][index] = hash_leaf(&self.state, index, data);
+        let mut index = index;
+        for level in 1..self.levels.len() {
+            let (lower, upper) = self.levels.split_at_mut(level);
+            let lower = &lower[level - 1];
+            let parent = index / 2;
+            let left = parent * 2;
+            upper[0][parent] = if left + 1 < lower.len() {
+                hash_node(&self.state, lower[left], lower[left + 1])
+            } else {
+                lower[left]
+            };
+            index = parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_matches_merkle_tree() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        for len in [0, 1, 63, 64, 65, 1000, 4096, 10_000] {
+            let input = data(len);
+            assert_eq!(hash(&state, &input, 64), MerkleTree::new(&state, &input, 64).root());
+        }
+    }
+
+    #[test]
+    fn test_leaf_size_and_length_matter() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        let input = data(10_000);
+        assert_ne!(hash(&state, &input, 64), hash(&state, &input, 128));
+        assert_ne!(hash(&state, &input, 64), hash(&state, &input[..9_999], 64));
+        let mut swapped = input.clone();
+        swapped[..64].copy_from_slice(&input[64..128]);
+        swapped[64..128].copy_from_slice(&input[..64]);
+        assert_ne!(hash(&state, &input, 64), hash(&state, &swapped, 64));
+        assert_ne!(hash(&state, &[], 64), hash(&state, &[0], 64));
+    }
+
+    #[test]
+    fn test_update_leaf() {
+        let state = RandomState::with_seeds(5, 6, 7, 8);
+        let mut input = data(10_050);
+        let mut tree = MerkleTree::new(&state, &input, 100);
+        assert_eq!(tree.leaf_count(), 101);
+        for &index in &[0_usize, 37, 99, 100] {
+            let start = index * 100;
+            let end = (start + 100).min(input.len());
+            input[start] ^= 0xff;
+            tree.update_leaf(index, &input[start..end]);
+            assert_eq!(tree.root(), hash(&state, &input, 100));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_is_deterministic() {
+        let state = RandomState::with_seeds(1, 2, 3, 4);
+        let input = data(1_000_003);
+        let expected = hash(&state, &input, 4096);
+        assert_eq!(hash_parallel(&state, &input, 4096), expected);
+        assert_eq!(MerkleTree::new_parallel(&state, &input, 4096).root(), expected);
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+        assert_eq!(pool.install(|| hash_parallel(&state, &input, 4096)), expected);
+    }
+}
@@ -186,6 +186,98 @@ fn bench_map(c: &mut Criterion) {
     }
 }
 
+fn bench_random_state(c: &mut Criterion) {
+    #[cfg(feature = "std")]
+    {
+        use std::sync::{Arc, Barrier};
+        use std::time::Instant;
+
+        const THREADS: usize = 8;
+
+        /// Measures the time for each of `THREADS` threads to create one `RandomState` at the same time. The threads
+        /// are started before, and joined after, the timed section, so only the creation itself is measured.
+        fn create_in_parallel(b: &mut criterion::Bencher, create: fn() -> RandomState) {
+            b.iter_custom(|iters| {
+                let barrier = Arc::new(Barrier::new(THREADS + 1));
+                let handles: Vec<_> = (0..THREADS)
+                    .map(|_| {
+                        let barrier = barrier.clone();
+                        std::thread::spawn(move || {
+                            barrier.wait();
+                            for _ in 0..iters {
+                                black_box(create());
+                            }
+                            barrier.wait();
+                        })
+                    })
+                    .collect();
+                barrier.wait();
+                let start = Instant::now();
+                barrier.wait();
+                let elapsed = start.elapsed();
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+                elapsed
+            })
+        }
+
+        let mut group = c.benchmark_group("random_state");
+        group.bench_function("new", |b| b.iter(|| black_box(RandomState::new())));
+        group.bench_function("new_thread_local", |b| {
+            b.iter(|| black_box(RandomState::new_thread_local()))
+        });
+        group.bench_function("new-contended", |b| create_in_parallel(b, RandomState::new));
+        group.bench_function("new_thread_local-contended", |b| {
+            create_in_parallel(b, RandomState::new_thread_local)
+        });
+    }
+}
+
+fn bench_derive(c: &mut Criterion) {
+    #[derive(Hash)]
+    struct Std {
+        a: u8,
+        b: u16,
+        c: u32,
+        d: u32,
+        e: bool,
+    }
+    #[derive(ahash_derive::AHash)]
+    struct Packed {
+        a: u8,
+        b: u16,
+        c: u32,
+        d: u32,
+        e: bool,
+    }
+
+    let state = RandomState::with_seeds(1, 2, 3, 4);
+    let mut group = c.benchmark_group("derive");
+    group.bench_function("derive(Hash)", |b| {
+        b.iter(|| {
+            state.hash_one(black_box(Std {
+                a: 1,
+                b: 2,
+                c: 3,
+                d: 4,
+                e: true,
+            }))
+        })
+    });
+    group.bench_function("derive(AHash)", |b| {
+        b.iter(|| {
+            state.hash_one(black_box(Packed {
+                a: 1,
+                b: 2,
+                c: 3,
+                d: 4,
+                e: true,
+            }))
+        })
+    });
+}
+
 criterion_main!(benches);
 
 criterion_group!(
@@ -195,5 +287,7 @@ criterion_group!(
     bench_fnv,
     bench_sea,
     bench_sip,
-    bench_map
+    bench_map,
+    bench_random_state,
+    bench_derive
 );
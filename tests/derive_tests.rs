@@ -0,0 +1,210 @@
+#![cfg(feature = "std")]
+
+use ahash::{AHashKey, AHashKeyState, AHashMap, RandomState};
+use std::hash::BuildHasher;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ahash_derive::AHashKey)]
+struct UserId(u64);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, ahash_derive::AHashKey)]
+struct Name {
+    value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, ahash_derive::AHashKey)]
+struct Wrapper<T>(T);
+
+fn hasher_type<K: AHashKey + ?Sized>() -> &'static str {
+    std::any::type_name::<K::Hasher>()
+}
+
+#[test]
+fn test_derived_key_uses_field_hasher() {
+    assert_eq!(hasher_type::<UserId>(), hasher_type::<u64>());
+    assert_eq!(hasher_type::<Name>(), hasher_type::<String>());
+    assert_eq!(hasher_type::<Wrapper<u128>>(), hasher_type::<u128>());
+    assert_eq!(hasher_type::<Wrapper<UserId>>(), hasher_type::<u64>());
+
+    let state = RandomState::with_seeds(1, 2, 3, 4);
+    assert_eq!(state.hash_key(&UserId(5)), state.hash_key(&5_u64));
+    assert_eq!(state.hash_key(&Name { value: "foo".into() }), state.hash_key("foo"));
+    assert_ne!(state.hash_key(&UserId(5)), state.hash_key(&UserId(6)));
+}
+
+#[test]
+fn test_derived_key_in_map() {
+    let mut map: AHashMap<UserId, u64, AHashKeyState<UserId>> = AHashMap::with_key_hasher();
+    for i in 0..1000 {
+        map.insert(UserId(i), i * 2);
+    }
+    for i in 0..1000 {
+        assert_eq!(map.get(&UserId(i)), Some(&(i * 2)));
+    }
+    let state = AHashKeyState::<Name>::with_random_state(RandomState::with_seeds(1, 2, 3, 4));
+    assert_eq!(
+        state.hash_one(&Name { value: "bar".into() }),
+        RandomState::with_seeds(1, 2, 3, 4).hash_key("bar")
+    );
+}
+
+/// A key whose `Hash` impl makes different calls than that of its field.
+#[derive(PartialEq, Eq, ahash_derive::AHashKey)]
+struct Labeled(u64);
+
+impl std::hash::Hash for Labeled {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(b"label");
+        state.write_u128(self.0 as u128);
+    }
+}
+
+#[test]
+fn test_derived_key_with_custom_hash() {
+    let state = RandomState::with_seeds(1, 2, 3, 4);
+    assert_ne!(state.hash_key(&Labeled(1)), state.hash_key(&Labeled(2)));
+    let mut map: AHashMap<Labeled, u64, AHashKeyState<Labeled>> = AHashMap::with_key_hasher();
+    for i in 0..1000 {
+        map.insert(Labeled(i), i);
+    }
+    assert!((0..1000).all(|i| map.get(&Labeled(i)) == Some(&i)));
+}
+
+/// Records the calls made to it.
+#[derive(Default)]
+struct Recorder(Vec<Call>);
+
+#[derive(Debug, PartialEq)]
+enum Call {
+    Bytes(Vec<u8>),
+    U8(u8),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+}
+
+impl std::hash::Hasher for Recorder {
+    fn finish(&self) -> u64 {
+        self.0.len() as u64
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.push(Call::Bytes(bytes.to_vec()));
+    }
+    fn write_u8(&mut self, i: u8) {
+        self.0.push(Call::U8(i));
+    }
+    fn write_u64(&mut self, i: u64) {
+        self.0.push(Call::U64(i));
+    }
+    fn write_u128(&mut self, i: u128) {
+        self.0.push(Call::U128(i));
+    }
+    fn write_usize(&mut self, i: usize) {
+        self.0.push(Call::Usize(i));
+    }
+}
+
+fn calls<T: std::hash::Hash>(value: &T) -> Vec<Call> {
+    let mut recorder = Recorder::default();
+    value.hash(&mut recorder);
+    recorder.0
+}
+
+#[derive(ahash_derive::AHash)]
+struct Small {
+    a: u8,
+    b: i16,
+    c: bool,
+    d: char,
+}
+
+#[derive(ahash_derive::AHash)]
+struct Wide(u64, i32, u32, u64, u8);
+
+#[derive(ahash_derive::AHash)]
+struct Mixed {
+    id: u32,
+    name: String,
+    #[ahash(skip)]
+    _cache: u64,
+    #[ahash(with = hash_len)]
+    tags: Vec<u8>,
+    flag: bool,
+    big: i128,
+}
+
+fn hash_len<H: std::hash::Hasher>(value: &[u8], state: &mut H) {
+    state.write_u64(value.len() as u64);
+}
+
+#[derive(ahash_derive::AHash)]
+struct Generic<T> {
+    value: T,
+    count: u16,
+}
+
+#[test]
+fn test_adjacent_fields_are_packed() {
+    let small = Small {
+        a: 0x12,
+        b: -2,
+        c: true,
+        d: 'x',
+    };
+    assert_eq!(
+        calls(&small),
+        vec![Call::U64(0x12 | 0xfffe << 8 | 1 << 24 | ('x' as u64) << 32)]
+    );
+    assert_eq!(
+        calls(&Wide(1, -1, 3, 4, 5)),
+        vec![Call::U128(1 | 0xffff_ffff << 64 | 3 << 96), Call::U128(4 | 5 << 64),]
+    );
+}
+
+#[test]
+fn test_skip_with_and_unpacked_fields() {
+    let mixed = Mixed {
+        id: 7,
+        name: "foo".into(),
+        _cache: 99,
+        tags: vec![1, 2, 3],
+        flag: true,
+        big: -1,
+    };
+    assert_eq!(
+        calls(&mixed),
+        vec![
+            Call::U64(7),
+            Call::Bytes(b"foo".to_vec()),
+            Call::U8(0xff),
+            Call::U64(3),
+            Call::U64(1),
+            Call::U128(u128::MAX),
+        ]
+    );
+    // Generic fields are not packed, as their type is not known to the macro.
+    assert_eq!(
+        calls(&Generic { value: 5_u8, count: 2 }),
+        vec![Call::U8(5), Call::U64(2)]
+    );
+    assert_eq!(
+        calls(&Generic { value: "a", count: 2 }),
+        vec![Call::Bytes(b"a".to_vec()), Call::U8(0xff), Call::U64(2)]
+    );
+}
+
+#[test]
+fn test_packed_hash_is_consistent() {
+    let state = RandomState::with_seeds(1, 2, 3, 4);
+    let mixed = |cache, tags: &[u8], big| Mixed {
+        id: 7,
+        name: "foo".into(),
+        _cache: cache,
+        tags: tags.to_vec(),
+        flag: true,
+        big,
+    };
+    assert_eq!(state.hash_one(mixed(1, &[1], 0)), state.hash_one(mixed(2, &[2], 0)));
+    assert_ne!(state.hash_one(mixed(1, &[1], 0)), state.hash_one(mixed(1, &[1, 2], 0)));
+    assert_ne!(state.hash_one(mixed(1, &[1], 0)), state.hash_one(mixed(1, &[1], 1)));
+    assert_ne!(state.hash_one(Wide(1, 2, 3, 4, 5)), state.hash_one(Wide(1, 2, 3, 4, 6)));
+}
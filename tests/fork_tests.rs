@@ -0,0 +1,136 @@
+//! Checks that forked children get independent keys after reseeding.
+//!
+//! This does not use the libtest harness: it runs tests on several threads, and a child forked from a multi threaded
+//! process may only make async-signal-safe calls until it execs. These children allocate, so the forks are made from
+//! `main` before any other thread exists.
+
+#[cfg(all(unix, feature = "std", feature = "runtime-rng"))]
+mod fork {
+    use ahash::{AHasher, RandomState};
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+    use std::hash::BuildHasherDefault;
+
+    pub type FixedMap = HashMap<u64, u64, BuildHasherDefault<AHasher>>;
+
+    /// Hashes from constructors which depend on process wide state, followed by a lookup in a map built by the
+    /// parent.
+    fn sample(map: &FixedMap) -> [u64; 4] {
+        [
+            RandomState::new().hash_one(1_u64),
+            RandomState::new_thread_local().hash_one(1_u64),
+            RandomState::with_seed(7).hash_one(1_u64),
+            map.get(&1).copied().unwrap_or(0),
+        ]
+    }
+
+    /// Forks, runs `in_child` followed by `sample` in the child, and returns the child's sample.
+    fn sample_from_child(map: &FixedMap, in_child: fn()) -> [u64; 4] {
+        let mut fds = [0; 2];
+        assert_eq!(0, unsafe { libc::pipe(fds.as_mut_ptr()) });
+        let pid = unsafe { libc::fork() };
+        assert!(pid >= 0, "fork failed");
+        if pid == 0 {
+            in_child();
+            let bytes: Vec<u8> = sample(map).iter().flat_map(|h| h.to_ne_bytes()).collect();
+            unsafe {
+                libc::write(fds[1], bytes.as_ptr().cast(), bytes.len());
+                libc::_exit(0);
+            }
+        }
+        let mut bytes = [0_u8; 32];
+        let mut read = 0;
+        while read < bytes.len() {
+            let n = unsafe { libc::read(fds[0], bytes[read..].as_mut_ptr().cast(), bytes.len() - read) };
+            assert!(n > 0, "Failed to read from child");
+            read += n as usize;
+        }
+        let mut status = 0;
+        unsafe {
+            libc::waitpid(pid, &mut status, 0);
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+        let mut result = [0; 4];
+        for (i, chunk) in bytes.chunks(8).enumerate() {
+            result[i] = u64::from_ne_bytes(chunk.try_into().unwrap());
+        }
+        result
+    }
+
+    /// Random keys must differ between the processes, while fixed keys, and maps which use them, must not.
+    fn assert_independent(a: [u64; 4], b: [u64; 4]) {
+        assert_ne!(a[0], b[0], "RandomState::new is the same in both processes");
+        assert_ne!(
+            a[1], b[1],
+            "RandomState::new_thread_local is the same in both processes"
+        );
+        assert_eq!(a[2], b[2], "RandomState::with_seed changed");
+        assert_eq!(a[3], 42, "Map lookup failed");
+        assert_eq!(b[3], 42, "Map lookup failed");
+    }
+
+    fn fixed_map() -> FixedMap {
+        let mut map = FixedMap::default();
+        map.insert(1, 42);
+        map
+    }
+
+    pub fn test_reseed_in_child() {
+        let map = fixed_map();
+        sample(&map);
+        let child = sample_from_child(&map, ahash::random_state::reseed);
+        assert_independent(child, sample(&map));
+        let other_child = sample_from_child(&map, ahash::random_state::reseed);
+        assert_independent(child, other_child);
+    }
+
+    /// After replacing the fixed seeds every constructor differs, and a map built by the parent with
+    /// `BuildHasherDefault` no longer finds its entry in the child.
+    pub fn test_reseed_fixed_seeds_in_child() {
+        let map = fixed_map();
+        let parent = sample(&map);
+        let child = sample_from_child(&map, ahash::random_state::reseed_fixed_seeds);
+        assert_ne!(child[0], parent[0], "RandomState::new is the same in both processes");
+        assert_ne!(
+            child[1], parent[1],
+            "RandomState::new_thread_local is the same in both processes"
+        );
+        assert_ne!(
+            child[2], parent[2],
+            "RandomState::with_seed is the same in both processes"
+        );
+        assert_eq!(child[3], 0, "Map lookup used the parent's seeds");
+        let other_child = sample_from_child(&map, ahash::random_state::reseed_fixed_seeds);
+        assert_ne!(
+            child[2], other_child[2],
+            "RandomState::with_seed is the same in both children"
+        );
+        assert_eq!(sample(&map)[2..], parent[2..], "The parent's seeds changed");
+    }
+
+    #[cfg(feature = "atfork")]
+    pub fn test_reseed_on_fork() {
+        ahash::random_state::reseed_on_fork().unwrap();
+        ahash::random_state::reseed_on_fork().unwrap();
+        let map = fixed_map();
+        sample(&map);
+        let child = sample_from_child(&map, || {});
+        assert_independent(child, sample(&map));
+    }
+}
+
+fn main() {
+    #[cfg(all(unix, feature = "std", feature = "runtime-rng"))]
+    {
+        fork::test_reseed_in_child();
+        println!("test test_reseed_in_child ... ok");
+        fork::test_reseed_fixed_seeds_in_child();
+        println!("test test_reseed_fixed_seeds_in_child ... ok");
+        #[cfg(feature = "atfork")]
+        {
+            fork::test_reseed_on_fork();
+            println!("test test_reseed_on_fork ... ok");
+        }
+    }
+}
@@ -0,0 +1,26 @@
+#![cfg(all(feature = "rand_core", feature = "std"))]
+
+use ahash::random_state::{set_random_source, RngRandomSource};
+use ahash::RandomState;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Installing a seeded generator as the random source makes every `RandomState::new()` reproducible.
+/// (This is in its own test binary because the random source can only be set once per process.)
+#[test]
+fn test_seeded_random_source() {
+    assert_ne!(ahash::build_info().rng_source, ahash::RngSource::Custom);
+    set_random_source(RngRandomSource::new(StdRng::seed_from_u64(42))).unwrap();
+    assert_eq!(ahash::build_info().rng_source, ahash::RngSource::Custom);
+    let mut expected = StdRng::seed_from_u64(42);
+    for _ in 0..10 {
+        let actual = RandomState::new();
+        let expected = RandomState::from_rng(&mut expected);
+        assert_eq!(actual.hash_one("test"), expected.hash_one("test"));
+    }
+    let map: ahash::AHashMap<u32, u32> = ahash::AHashMap::new();
+    assert_eq!(
+        map.hasher().hash_one(1_u32),
+        RandomState::from_rng(&mut expected).hash_one(1_u32)
+    );
+}
@@ -0,0 +1,523 @@
+//! A port of the most important [SMHasher](https://github.com/rurban/smhasher) suites.
+//!
+//! These are the same kinds of keysets and statistics SMHasher uses, scaled so that each suite runs in a few seconds
+//! with optimizations. They are ignored by default (CI runs them in a separate job), run them with:
+//!
+//! ```text
+//! cargo test --release --test smhasher -- --ignored --nocapture
+//! RUSTFLAGS="-C target-feature=+aes" cargo test --release --test smhasher -- --ignored --nocapture
+//! ```
+//!
+//! Every keyset is checked for collisions in the full 64 bit hash and in its high and low 32 bits (a random function
+//! must have less than a one in a million chance of producing that many), and for bias in every window of 8 to 20 bits of the hash
+//! (which must stay below 1%, SMHasher's threshold).
+use ahash::RandomState;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hasher};
+
+fn hash(state: &RandomState, key: &[u8]) -> u64 {
+    let mut hasher = state.build_hasher();
+    hasher.write(key);
+    hasher.finish()
+}
+
+fn state() -> RandomState {
+    RandomState::with_seeds(
+        0x243f_6a88_85a3_08d3,
+        0x1319_8a2e_0370_7344,
+        0xa409_3822_299f_31d0,
+        0x082e_fa98_ec4e_6c89,
+    )
+}
+
+fn random_bytes(rng: &mut StdRng, len: usize) -> Vec<u8> {
+    let mut key = vec![0; len];
+    rng.fill(&mut key[..]);
+    key
+}
+
+/// The number of collisions expected when `keys` values are thrown uniformly into `2^bits` buckets.
+fn expected_collisions(keys: usize, bits: u32) -> f64 {
+    let n = keys as f64;
+    let m = 2_f64.powi(bits as i32);
+    n + m * (n * (-1.0 / m).ln_1p()).exp_m1()
+}
+
+/// The probability of a Poisson distributed variable with mean `expected` being at least `actual`.
+fn poisson_tail(expected: f64, actual: usize) -> f64 {
+    let ln_factorial: f64 = (1..=actual).map(|i| (i as f64).ln()).sum();
+    let mut term = (-expected + actual as f64 * expected.ln() - ln_factorial).exp();
+    let mut sum = term;
+    let mut k = actual;
+    while term > sum * 1e-12 {
+        k += 1;
+        term *= expected / k as f64;
+        sum += term;
+    }
+    sum.min(1.0)
+}
+
+fn count_collisions(mut values: Vec<u64>) -> usize {
+    values.sort_unstable();
+    values.windows(2).filter(|w| w[0] == w[1]).count()
+}
+
+fn check_collisions(name: &str, hashes: &[u64]) {
+    let checks = [
+        ("64-bit", 64, 0, u64::MAX),
+        ("high 32-bit", 32, 32, 0xFFFF_FFFF),
+        ("low 32-bit", 32, 0, 0xFFFF_FFFF),
+    ];
+    for &(label, bits, shift, mask) in checks.iter() {
+        let actual = count_collisions(hashes.iter().map(|&h| (h >> shift) & mask).collect());
+        let expected = if bits == 64 {
+            let n = hashes.len() as f64;
+            n * (n - 1.0) / 2_f64.powi(65)
+        } else {
+            expected_collisions(hashes.len(), bits)
+        };
+        println!(
+            "{}: {} collisions - expected {:.1}, actual {} ({:.2}x)",
+            name,
+            label,
+            expected,
+            actual,
+            actual as f64 / expected
+        );
+        assert!(
+            poisson_tail(expected, actual) > 1e-6,
+            "{}: {} collisions {} are far above the expected {:.1}",
+            name,
+            label,
+            actual,
+            expected
+        );
+    }
+}
+
+/// SMHasher's bias score: 0 for a perfectly flat distribution, approaching 1 as everything lands in one bin.
+fn score(bins: &[u32], keys: usize) -> f64 {
+    let n = bins.len() as f64;
+    let k = keys as f64;
+    let sum_squares: f64 = bins.iter().map(|&b| (b as f64) * (b as f64)).sum();
+    let rms = (sum_squares / n).sqrt();
+    let fill = (k * k - 1.0) / (n * rms * rms - k);
+    1.0 - fill / n
+}
+
+fn check_distribution(name: &str, hashes: &[u64]) {
+    // At least five keys per bin are needed to reliably detect biases down to 1%.
+    let mut max_width = 20;
+    while hashes.len() < 5 << max_width {
+        max_width -= 1;
+    }
+    assert!(max_width >= 8, "{}: too few keys to test the distribution", name);
+    let mut bins = vec![0_u32; 1 << max_width];
+    let (mut worst, mut worst_start, mut worst_width) = (0.0, 0, 0);
+    for start in 0..64 {
+        bins.iter_mut().for_each(|b| *b = 0);
+        for &h in hashes {
+            bins[(h.rotate_right(start) & ((1 << max_width) - 1)) as usize] += 1;
+        }
+        let mut width = max_width;
+        loop {
+            let score = score(&bins[..1 << width], hashes.len());
+            if score > worst {
+                worst = score;
+                worst_start = start;
+                worst_width = width;
+            }
+            if width == 8 {
+                break;
+            }
+            width -= 1;
+            let half = 1 << width;
+            for i in 0..half {
+                bins[i] += bins[i + half];
+            }
+        }
+    }
+    println!(
+        "{}: worst bias is the {}-bit window at bit {} - {:.3}%",
+        name,
+        worst_width,
+        worst_start,
+        worst * 100.0
+    );
+    assert!(
+        worst < 0.01,
+        "{}: the {}-bit window at bit {} is biased by {:.3}%",
+        name,
+        worst_width,
+        worst_start,
+        worst * 100.0
+    );
+}
+
+fn check_keyset(name: &str, hashes: &[u64]) {
+    println!("{}: {} keys", name, hashes.len());
+    check_collisions(name, hashes);
+    check_distribution(name, hashes);
+}
+
+fn hash_keys<I: IntoIterator<Item = Vec<u8>>>(state: &RandomState, keys: I) -> Vec<u64> {
+    keys.into_iter().map(|key| hash(state, &key)).collect()
+}
+
+/// Calls `f` with every key of `bits` bits that has between one and `max_set` bits set.
+fn for_each_sparse_key<F: FnMut(&[u8])>(bits: usize, max_set: usize, f: &mut F) {
+    fn recurse<F: FnMut(&[u8])>(key: &mut [u8], start: usize, remaining: usize, f: &mut F) {
+        for bit in start..key.len() * 8 {
+            key[bit / 8] ^= 1 << (bit % 8);
+            f(key);
+            if remaining > 1 {
+                recurse(key, bit + 1, remaining - 1, f);
+            }
+            key[bit / 8] ^= 1 << (bit % 8);
+        }
+    }
+    recurse(&mut vec![0; bits / 8], 0, max_set, f);
+}
+
+/// Calls `f` with every concatenation of one to `max_blocks` blocks drawn from `blocks`.
+fn for_each_combination<F: FnMut(&[u8])>(blocks: &[u32], max_blocks: usize, f: &mut F) {
+    fn recurse<F: FnMut(&[u8])>(key: &mut Vec<u8>, blocks: &[u32], remaining: usize, f: &mut F) {
+        for block in blocks {
+            key.extend_from_slice(&block.to_le_bytes());
+            f(key);
+            if remaining > 1 {
+                recurse(key, blocks, remaining - 1, f);
+            }
+            key.truncate(key.len() - 4);
+        }
+    }
+    recurse(&mut Vec::new(), blocks, max_blocks, f);
+}
+
+/// The same key always hashes the same, flipping any bit changes the hash, and the bytes around the key in memory
+/// don't matter.
+#[test]
+#[ignore]
+fn sanity() {
+    let state = state();
+    let mut rng = StdRng::seed_from_u64(1);
+    for len in 0..=256 {
+        let mut key = random_bytes(&mut rng, len);
+        let expected = hash(&state, &key);
+        assert_eq!(
+            expected,
+            hash(&state, &key.clone()),
+            "Sanity: unstable hash of {} bytes",
+            len
+        );
+        for bit in 0..len * 8 {
+            key[bit / 8] ^= 1 << (bit % 8);
+            assert_ne!(
+                hash(&state, &key),
+                expected,
+                "Sanity: flipping bit {} of {} bytes",
+                bit,
+                len
+            );
+            key[bit / 8] ^= 1 << (bit % 8);
+        }
+        for offset in 0..16 {
+            let mut buffer = random_bytes(&mut rng, len + 32);
+            buffer[offset..offset + len].copy_from_slice(&key);
+            assert_eq!(
+                hash(&state, &buffer[offset..offset + len]),
+                expected,
+                "Sanity: {} bytes at offset {} depend on their surroundings",
+                len,
+                offset
+            );
+        }
+    }
+}
+
+/// Appending zero bytes to a key always changes its hash.
+#[test]
+#[ignore]
+fn appended_zeroes() {
+    let state = state();
+    let mut rng = StdRng::seed_from_u64(2);
+    for _ in 0..1000 {
+        let mut key = random_bytes(&mut rng, 32);
+        let mut seen = HashSet::new();
+        for extra in 0..=256 {
+            assert!(
+                seen.insert(hash(&state, &key)),
+                "AppendedZeroes: {} appended zeroes collide",
+                extra
+            );
+            key.push(0);
+        }
+    }
+}
+
+/// Flipping any input bit flips each output bit with probability one half.
+#[test]
+#[ignore]
+fn avalanche() {
+    const SAMPLES: u32 = 300_000;
+    let state = state();
+    let mut rng = StdRng::seed_from_u64(3);
+    for &len in [4, 8, 12, 16, 24, 32, 64].iter() {
+        let mut flips = vec![[0_u32; 64]; len * 8];
+        let mut key = vec![0; len];
+        for _ in 0..SAMPLES {
+            rng.fill(&mut key[..]);
+            let original = hash(&state, &key);
+            for (bit, counts) in flips.iter_mut().enumerate() {
+                key[bit / 8] ^= 1 << (bit % 8);
+                let mut diff = hash(&state, &key) ^ original;
+                key[bit / 8] ^= 1 << (bit % 8);
+                while diff != 0 {
+                    counts[diff.trailing_zeros() as usize] += 1;
+                    diff &= diff - 1;
+                }
+            }
+        }
+        let mut worst = (0.0, 0, 0);
+        for (input, counts) in flips.iter().enumerate() {
+            for (output, &count) in counts.iter().enumerate() {
+                let bias = (2.0 * count as f64 / SAMPLES as f64 - 1.0).abs();
+                if bias > worst.0 {
+                    worst = (bias, input, output);
+                }
+            }
+        }
+        println!(
+            "Avalanche {} bytes: worst bias is {:.3}% (input bit {}, output bit {})",
+            len,
+            worst.0 * 100.0,
+            worst.1,
+            worst.2
+        );
+        assert!(
+            worst.0 < 0.01,
+            "Avalanche: {} byte keys are biased by {:.3}%",
+            len,
+            worst.0 * 100.0
+        );
+    }
+}
+
+/// Keys that are mostly zero with only a few bits set.
+#[test]
+#[ignore]
+fn sparse() {
+    let state = state();
+    for &(bits, max_set) in [
+        (32, 6),
+        (40, 6),
+        (48, 5),
+        (64, 4),
+        (96, 4),
+        (256, 3),
+        (512, 2),
+        (2048, 2),
+    ]
+    .iter()
+    {
+        let mut hashes = Vec::new();
+        for_each_sparse_key(bits, max_set, &mut |key| hashes.push(hash(&state, key)));
+        check_keyset(
+            &format!("Sparse {}-bit keys with up to {} bits set", bits, max_set),
+            &hashes,
+        );
+    }
+}
+
+/// Keys made of every sequence of a few four byte blocks.
+#[test]
+#[ignore]
+fn permutation() {
+    let state = state();
+    let low_bits: Vec<u32> = (0..8).collect();
+    let high_bits: Vec<u32> = (0..8).map(|i| i << 29).collect();
+    let cases: [(&str, &[u32], usize); 6] = [
+        ("low bits", &low_bits, 7),
+        ("high bits", &high_bits, 7),
+        ("hi-bit-null", &[0, 0x8000_0000], 20),
+        ("lo-bit-null", &[0, 1], 20),
+        ("0x80000000", &[0x8000_0000], 256),
+        ("0xFFFFFFFF", &[0xFFFF_FFFF], 256),
+    ];
+    for (name, blocks, max_blocks) in cases.iter() {
+        let mut hashes = Vec::new();
+        for_each_combination(blocks, *max_blocks, &mut |key| hashes.push(hash(&state, key)));
+        if hashes.len() < 256 * 5 {
+            assert_eq!(count_collisions(hashes.clone()), 0, "Combination {}: collisions", name);
+            println!("Combination {}: {} keys without collisions", name, hashes.len());
+        } else {
+            check_keyset(&format!("Combination {}", name), &hashes);
+        }
+    }
+}
+
+/// Keys made of a random block repeated several times.
+#[test]
+#[ignore]
+fn cyclic() {
+    const KEYS: usize = 1_000_000;
+    const CYCLES: usize = 8;
+    let state = state();
+    let mut rng = StdRng::seed_from_u64(4);
+    for &block in [3, 4, 5, 8, 12, 16].iter() {
+        // Short blocks repeat by chance, only distinct keys count.
+        let mut blocks = HashSet::new();
+        while blocks.len() < KEYS {
+            blocks.insert(random_bytes(&mut rng, block));
+        }
+        let hashes = hash_keys(&state, blocks.into_iter().map(|b| b.repeat(CYCLES)));
+        check_keyset(&format!("Cyclic {} cycles of {} bytes", CYCLES, block), &hashes);
+    }
+}
+
+/// Every key of a given length with at most two non-zero bytes.
+#[test]
+#[ignore]
+fn two_bytes() {
+    let state = state();
+    for &len in [4, 8, 12, 16].iter() {
+        let mut hashes = Vec::new();
+        let mut key = vec![0_u8; len];
+        hashes.push(hash(&state, &key));
+        for i in 0..len {
+            for a in 1..=255 {
+                key[i] = a;
+                hashes.push(hash(&state, &key));
+                for j in i + 1..len {
+                    for b in 1..=255 {
+                        key[j] = b;
+                        hashes.push(hash(&state, &key));
+                    }
+                    key[j] = 0;
+                }
+            }
+            key[i] = 0;
+        }
+        check_keyset(&format!("TwoBytes {} byte keys", len), &hashes);
+    }
+}
+
+/// Text keys which only differ in four alphanumeric characters.
+#[test]
+#[ignore]
+fn text() {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let state = state();
+    for &(prefix, suffix) in [
+        ("Foo", "Bar"),
+        ("FooBar", ""),
+        ("", "FooBar"),
+        ("FooBarBazQux", "QuuxCorge"),
+    ]
+    .iter()
+    {
+        let mut hashes = Vec::with_capacity(CHARS.len().pow(4));
+        let mut key = format!("{}0000{}", prefix, suffix).into_bytes();
+        let at = prefix.len();
+        for &a in CHARS {
+            for &b in CHARS {
+                for &c in CHARS {
+                    for &d in CHARS {
+                        key[at..at + 4].copy_from_slice(&[a, b, c, d]);
+                        hashes.push(hash(&state, &key));
+                    }
+                }
+            }
+        }
+        check_keyset(&format!("Text '{}XXXX{}'", prefix, suffix), &hashes);
+    }
+}
+
+/// Keys of nothing but zeroes, which differ only in their length.
+#[test]
+#[ignore]
+fn zeroes() {
+    let state = state();
+    let zeroes = vec![0; 64 * 1024];
+    let hashes: Vec<u64> = (0..=zeroes.len()).map(|len| hash(&state, &zeroes[..len])).collect();
+    check_keyset("Zeroes", &hashes);
+}
+
+/// The same key hashed with many different seeds.
+#[test]
+#[ignore]
+fn seed() {
+    const SEEDS: u64 = 1_000_000;
+    for &key in ["", "a", "The quick brown fox jumps over the lazy dog"].iter() {
+        for position in 0..4 {
+            let hashes: Vec<u64> = (0..SEEDS)
+                .map(|seed| {
+                    let mut seeds = [0; 4];
+                    seeds[position] = seed;
+                    hash(
+                        &RandomState::with_seeds(seeds[0], seeds[1], seeds[2], seeds[3]),
+                        key.as_bytes(),
+                    )
+                })
+                .collect();
+            check_keyset(&format!("Seed k{} with key '{}'", position, key), &hashes);
+        }
+    }
+}
+
+/// Flipping a few bits of a key never produces the same hash.
+#[test]
+#[ignore]
+fn differential() {
+    let state = state();
+    let mut rng = StdRng::seed_from_u64(5);
+    for &(bits, max_set, keys) in [(64, 5, 10), (128, 4, 5), (256, 3, 10), (1024, 2, 20)].iter() {
+        let mut tests = 0_u64;
+        for _ in 0..keys {
+            let key = random_bytes(&mut rng, bits / 8);
+            let original = hash(&state, &key);
+            let mut changed = key.clone();
+            for_each_sparse_key(bits, max_set, &mut |diff| {
+                for (c, (k, d)) in changed.iter_mut().zip(key.iter().zip(diff)) {
+                    *c = k ^ d;
+                }
+                tests += 1;
+                assert_ne!(
+                    hash(&state, &changed),
+                    original,
+                    "Differential: {:x?} collides with {:x?}",
+                    changed,
+                    key
+                );
+            });
+        }
+        println!(
+            "Differential {}-bit keys, up to {} bits: {} tests, no collisions",
+            bits, max_set, tests
+        );
+    }
+}
+
+/// The differences between the hashes of keys which differ in a single bit are uniformly distributed.
+#[test]
+#[ignore]
+fn differential_distribution() {
+    const KEYS: usize = 100_000;
+    let state = state();
+    let mut rng = StdRng::seed_from_u64(6);
+    for &len in [8, 16].iter() {
+        for bit in 0..len * 8 {
+            let hashes: Vec<u64> = (0..KEYS)
+                .map(|_| {
+                    let mut key = random_bytes(&mut rng, len);
+                    let original = hash(&state, &key);
+                    key[bit / 8] ^= 1 << (bit % 8);
+                    original ^ hash(&state, &key)
+                })
+                .collect();
+            check_keyset(&format!("DiffDist {} byte keys, bit {}", len, bit), &hashes);
+        }
+    }
+}